@@ -17,12 +17,12 @@ fn background(ray: &Ray) -> Color {
     if let Some(t) = hit_sphere(&Point3::new(0.0, 0.0, -1.0), 0.5, ray) {
         if t > 0.0 {
             let n = (ray.position_after(t) - Vec3::new(0.0, 0.0, -1.0)).unit();
-            return (0.5 * (n + Vec3::new(1.0, 1.0, 1.0))).into_color(1, false);
+            return (0.5 * (n + Vec3::new(1.0, 1.0, 1.0))).into();
         }
     }
     let unit = ray.direction.unit();
     let t = 0.5 * (unit.y + 1.0);
-    Color::new(1.0, 1.0, 1.0).gradient(&Color::new(0.5, 0.7, 1.0), t)
+    Color::new(1.0, 1.0, 1.0).lerp(&Color::new(0.5, 0.7, 1.0), t)
 }
 
 fn main() {
@@ -36,11 +36,11 @@ fn background(ray: &Ray) -> Color {
         .flatten()
         .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
     {
-        return (0.5 * (n + Vec3::new(1.0, 1.0, 1.0))).into_color(1, false);
+        return (0.5 * (n + Vec3::new(1.0, 1.0, 1.0))).into();
     }
     let unit = ray.direction.unit();
     let t = 0.5 * (unit.y + 1.0);
-    Color::new(1.0, 1.0, 1.0).gradient(&Color::new(0.5, 0.7, 1.0), t)
+    Color::new(1.0, 1.0, 1.0).lerp(&Color::new(0.5, 0.7, 1.0), t)
 }
 
 fn main() {
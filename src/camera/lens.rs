@@ -0,0 +1,48 @@
+/// Brown-Conrady lens distortion model.
+///
+/// Coordinates are measured from optical axis and normalized by half of the picture height.
+/// Positive radial coefficients give barrel distortion, negative ones give pincushion.
+#[derive(Debug, Clone, Default)]
+pub struct LensDistortion {
+    k1: f64,
+    k2: f64,
+    k3: f64,
+    p1: f64,
+    p2: f64,
+}
+
+impl LensDistortion {
+    #[must_use]
+    pub const fn radial(k1: f64, k2: f64, k3: f64) -> Self {
+        Self {
+            k1,
+            k2,
+            k3,
+            p1: 0.0,
+            p2: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub const fn tangential(mut self, p1: f64, p2: f64) -> Self {
+        self.p1 = p1;
+        self.p2 = p2;
+        self
+    }
+
+    /// Map a point on picture to the direction it sees through the lens
+    #[must_use]
+    pub fn distort(&self, x: f64, y: f64) -> (f64, f64) {
+        let r2 = x.mul_add(x, y * y);
+        let radial = r2.mul_add(r2.mul_add(r2.mul_add(self.k3, self.k2), self.k1), 1.0);
+        let xd = x.mul_add(
+            radial,
+            (2.0 * self.p1 * x).mul_add(y, self.p2 * (2.0 * x).mul_add(x, r2)),
+        );
+        let yd = y.mul_add(
+            radial,
+            (2.0 * self.p2 * x).mul_add(y, self.p1 * (2.0 * y).mul_add(y, r2)),
+        );
+        (xd, yd)
+    }
+}
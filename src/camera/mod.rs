@@ -0,0 +1,573 @@
+use {
+    crate::{
+        environment::Environment,
+        hittable::{
+            collection::{HittableList, PickRecord, World},
+            medium::Atmosphere,
+        },
+        integrator::{Integrator, PathTracer},
+        material::PhaseFunction,
+        painter::{AlphaMode, Painter},
+        prelude::*,
+    },
+    std::{borrow::Cow, ops::Range, path::Path},
+};
+
+pub(crate) mod lens;
+pub(crate) mod physical;
+pub(crate) mod shutter;
+
+pub use {lens::LensDistortion, physical::PhysicalCamera, shutter::ShutterCurve};
+
+#[derive(Debug, Clone)]
+pub struct Camera {
+    origin: Point3,
+    forward: Vec3,
+    focus_distance: f64,
+    center: Point3,
+    lb: Point3,
+    horizontal_full: Vec3,
+    vertical_full: Vec3,
+    horizontal_unit: Vec3,
+    vertical_unit: Vec3,
+    shift: (f64, f64),
+    focus_plane_normal: Option<Vec3>,
+    distortion: Option<LensDistortion>,
+    aspect_ratio: f64,
+    aperture: f64,
+    shutter_speed: f64,
+    shutter_curve: ShutterCurve,
+    rolling_shutter: f64,
+    exposure: f64,
+}
+
+impl Camera {
+    pub(self) fn new(builder: CameraBuilder) -> Self {
+        let fov = builder.fov.to_radians();
+        let h = (fov / 2.0).tan();
+        let vh = 2.0 * h;
+        let vw = vh * builder.aspect_ratio;
+        let focus_distance = builder.focus_distance;
+
+        let w = (&builder.look_at - &builder.look_from).unit();
+        let horizontal_unit = w.cross(&builder.vup).unit();
+        let vertical_unit = horizontal_unit.cross(&w).unit();
+
+        let horizontal_full = focus_distance * vw * &horizontal_unit;
+        let vertical_full = focus_distance * vh * &vertical_unit;
+        let center = &builder.look_from + focus_distance * &w;
+        let lb = &center - &horizontal_full / 2.0 - &vertical_full / 2.0
+            + builder.shift.0 * &horizontal_full
+            + builder.shift.1 * &vertical_full;
+
+        let focus_plane_normal = if builder.tilt == 0.0 && builder.swing == 0.0 {
+            None
+        } else {
+            Some(
+                (&w + builder.tilt.to_radians().tan() * &vertical_unit
+                    + builder.swing.to_radians().tan() * &horizontal_unit)
+                    .unit(),
+            )
+        };
+
+        Self {
+            origin: builder.look_from,
+            forward: w,
+            focus_distance,
+            center,
+            lb,
+            horizontal_full,
+            vertical_full,
+            horizontal_unit,
+            vertical_unit,
+            shift: builder.shift,
+            focus_plane_normal,
+            distortion: builder.distortion,
+            aspect_ratio: builder.aspect_ratio,
+            aperture: builder.aperture,
+            shutter_speed: builder.shutter_speed,
+            shutter_curve: builder.shutter_curve,
+            rolling_shutter: builder.rolling_shutter,
+            exposure: builder.exposure,
+        }
+    }
+
+    fn distort(&self, u: f64, v: f64) -> (f64, f64) {
+        self.distortion.as_ref().map_or((u, v), |distortion| {
+            let x = 2.0f64.mul_add(u + self.shift.0, -1.0) * self.aspect_ratio;
+            let y = 2.0f64.mul_add(v + self.shift.1, -1.0);
+            let (x, y) = distortion.distort(x, y);
+            (
+                0.5f64.mul_add(x / self.aspect_ratio, 0.5) - self.shift.0,
+                0.5f64.mul_add(y, 0.5) - self.shift.1,
+            )
+        })
+    }
+
+    // Point on the focus plane which the pinhole ray through picture point (u, v) reaches
+    fn focus_point(&self, u: f64, v: f64) -> Point3 {
+        let (u, v) = self.distort(u, v);
+        let point = &self.lb + u * &self.horizontal_full + v * &self.vertical_full;
+        if let Some(normal) = &self.focus_plane_normal {
+            let direction = &point - &self.origin;
+            let cos = normal.dot(&direction);
+            if cos.abs() > f64::EPSILON {
+                let unit = normal.dot(&(&self.center - &self.origin)) / cos;
+                if unit > 0.0 {
+                    return &self.origin + unit * direction;
+                }
+            }
+        }
+        point
+    }
+
+    #[must_use]
+    pub const fn exposure(&self) -> f64 {
+        self.exposure
+    }
+
+    /// Move focus plane to a new distance, keep field of view unchanged
+    pub fn refocus(&mut self, distance: f64) {
+        debug_assert!(distance > 0.0, "distance = {}", distance);
+        let ratio = distance / self.focus_distance;
+        self.horizontal_full *= ratio;
+        self.vertical_full *= ratio;
+        self.focus_distance = distance;
+        self.center = &self.origin + distance * &self.forward;
+        self.lb = &self.center - &self.horizontal_full / 2.0 - &self.vertical_full / 2.0
+            + self.shift.0 * &self.horizontal_full
+            + self.shift.1 * &self.vertical_full;
+    }
+
+    /// Ray from lens center through picture point (u, v) when shutter opens
+    #[must_use]
+    pub fn center_ray(&self, u: f64, v: f64) -> Ray {
+        let direction = self.focus_point(u, v) - &self.origin;
+        Ray::new(self.origin.clone(), direction, 0.0).kind(RayKind::Camera)
+    }
+
+    /// Find what is seen at picture point (u, v) without rendering
+    #[must_use]
+    pub fn pick(&self, world: &World, u: f64, v: f64) -> Option<PickRecord> {
+        world.pick(&self.center_ray(u, v))
+    }
+
+    #[must_use]
+    pub fn ray(&self, u: f64, v: f64) -> Ray {
+        let rd = self.aperture / 2.0 * Vec3::random_unit_disk();
+        let offset = &self.horizontal_unit * rd.x + &self.vertical_unit * rd.y;
+        let origin = &self.origin + offset;
+        let direction = self.focus_point(u, v) - &origin;
+
+        let open_time = (1.0 - v) * self.rolling_shutter;
+        let time = self
+            .shutter_speed
+            .mul_add(self.shutter_curve.sample(), open_time);
+
+        Ray::new(origin, direction, time).kind(RayKind::Camera)
+    }
+
+    /// Time range from the first row starting exposure to the last row ending it
+    #[must_use]
+    pub fn time_range(&self) -> Range<f64> {
+        0.0..self.shutter_speed + self.rolling_shutter
+    }
+
+    #[must_use]
+    pub fn take_photo(&self, world: HittableList) -> TakePhotoSettings<'_> {
+        let world = World::new(world, self.time_range());
+        TakePhotoSettings::new(self, world)
+    }
+}
+
+#[derive(Debug)]
+pub struct TakePhotoSettings<'c> {
+    camera: Cow<'c, Camera>,
+    world: World,
+    integrator: Box<dyn Integrator>,
+    depth: usize,
+    picture_height: usize,
+    color_space: ColorSpace,
+    white_balance: Option<f64>,
+    alpha: Option<AlphaMode>,
+    samples: usize,
+    threads: usize,
+    parallel: bool,
+    spectral: bool,
+}
+
+impl<'c> TakePhotoSettings<'c> {
+    #[must_use]
+    pub fn new(camera: &'c Camera, world: World) -> Self {
+        Self {
+            camera: Cow::Borrowed(camera),
+            world,
+            integrator: Box::new(PathTracer::default()),
+            depth: 8,
+            picture_height: 108,
+            color_space: ColorSpace::SRGB,
+            white_balance: None,
+            alpha: None,
+            samples: 50,
+            threads: 0,
+            parallel: true,
+            spectral: false,
+        }
+    }
+
+    pub fn background<BG: Fn(&Ray) -> Color + Send + Sync + 'static>(mut self, bg: BG) -> Self {
+        self.world.set_bg(bg);
+        self
+    }
+
+    #[must_use]
+    pub fn environment<E: Environment + 'static>(mut self, environment: E) -> Self {
+        self.world.set_environment(environment);
+        self
+    }
+
+    #[must_use]
+    pub fn atmosphere<P: PhaseFunction + 'static>(mut self, atmosphere: Atmosphere<P>) -> Self {
+        self.world.set_atmosphere(atmosphere);
+        self
+    }
+
+    #[must_use]
+    pub fn integrator<I: Integrator + 'static>(mut self, integrator: I) -> Self {
+        self.integrator = Box::new(integrator);
+        self
+    }
+
+    #[must_use]
+    pub const fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    #[must_use]
+    pub const fn height(mut self, height: usize) -> Self {
+        self.picture_height = height;
+        self
+    }
+
+    /// Encode output by sRGB transfer function or not, same as setting color space to
+    /// `SRGB` or `LinearSRGB`
+    #[must_use]
+    pub const fn gamma(mut self, gamma: bool) -> Self {
+        self.color_space = if gamma {
+            ColorSpace::SRGB
+        } else {
+            ColorSpace::LinearSRGB
+        };
+        self
+    }
+
+    /// Color space of output image, default is sRGB
+    #[must_use]
+    pub const fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Make light of blackbody at the temperature in kelvin look white, default is no
+    /// adjustment
+    #[must_use]
+    pub fn white_balance(mut self, temperature: f64) -> Self {
+        debug_assert!(temperature > 0.0, "temperature = {}", temperature);
+        self.white_balance = Some(temperature);
+        self
+    }
+
+    /// Save with alpha channel stored in the mode, background seen directly is transparent.
+    /// Only PNG and BMP output are supported, decided by extension of the path
+    #[must_use]
+    pub const fn alpha(mut self, alpha_mode: AlphaMode) -> Self {
+        self.alpha = Some(alpha_mode);
+        self
+    }
+
+    #[must_use]
+    pub const fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    #[must_use]
+    pub const fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    #[must_use]
+    pub const fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Trace every sample at a random wavelength, so wavelength dependent effects like
+    /// dispersion can be rendered
+    #[must_use]
+    pub const fn spectral(mut self, spectral: bool) -> Self {
+        self.spectral = spectral;
+        self
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        // because picture height/width is always positive and small enough in practice
+        #[allow(
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation
+        )]
+        let width = (self.picture_height as f64 * self.camera.aspect_ratio).round() as usize;
+        width
+    }
+
+    #[allow(clippy::cast_precision_loss)] // because picture height/width is small enough in practice
+    fn pixel_uv(&self, x: usize, y: usize) -> Option<(f64, f64)> {
+        if x >= self.width() || y >= self.picture_height {
+            return None;
+        }
+        let u = (x as f64 + 0.5) / self.width() as f64;
+        let v = ((self.picture_height - 1 - y) as f64 + 0.5) / self.picture_height as f64;
+        Some((u, v))
+    }
+
+    /// Find what is seen at pixel (x, y), (0, 0) is the top left corner.
+    /// None if nothing there or the pixel is out of the picture
+    #[must_use]
+    pub fn pick(&self, x: usize, y: usize) -> Option<PickRecord> {
+        let (u, v) = self.pixel_uv(x, y)?;
+        self.camera.pick(&self.world, u, v)
+    }
+
+    /// Move focus plane to the object seen at pixel (x, y), unchanged if nothing there
+    /// or the pixel is out of the picture
+    #[must_use]
+    pub fn autofocus(mut self, x: usize, y: usize) -> Self {
+        let Some((u, v)) = self.pixel_uv(x, y) else {
+            return self;
+        };
+        let ray = self.camera.center_ray(u, v);
+        if let Some(record) = self.world.pick(&ray) {
+            let distance = (&record.point - &ray.origin).dot(&self.camera.forward);
+            if distance > 0.0 {
+                self.camera.to_mut().refocus(distance);
+            }
+        }
+        self
+    }
+
+    /// # Errors
+    /// When open or save to file failed, or alpha is enabled without a PNG or BMP path
+    #[allow(clippy::needless_pass_by_value)] // Directly used public API, add & will make it harder to use
+    pub fn shot<P: AsRef<Path>>(&self, path: Option<P>) -> std::io::Result<()> {
+        self.integrator.prepare(&self.world, self.depth);
+        let painter = Painter::new(self.width(), self.picture_height)
+            .color_space(self.color_space)
+            .samples(self.samples)
+            .threads(self.threads)
+            .parallel(self.parallel);
+        let painter = match self.white_balance {
+            Some(temperature) => painter.white_balance(temperature),
+            None => painter,
+        };
+        match self.alpha {
+            Some(alpha_mode) => painter
+                .alpha_mode(alpha_mode)
+                .draw_rgba(&path, |u, v| self.sample(u, v, true)),
+            None => painter.draw(&path, |u, v| self.sample(u, v, false).0),
+        }
+    }
+
+    // Radiance through the film position, and alpha if asked
+    fn sample(&self, u: f64, v: f64, with_alpha: bool) -> (Vec3, f64) {
+        let wavelength = if self.spectral {
+            Some(Spectrum::sample_wavelength())
+        } else {
+            None
+        };
+        let ray = self.camera.ray(u, v).wavelength(wavelength);
+        let (radiance, alpha) = if with_alpha {
+            self.integrator
+                .radiance_alpha(&ray, &self.world, self.depth)
+        } else {
+            (self.integrator.radiance(&ray, &self.world, self.depth), 1.0)
+        };
+        let radiance = match wavelength {
+            Some(wavelength) => {
+                Spectrum::to_rgb(Spectrum::from_rgb(&radiance, wavelength), wavelength)
+            }
+            None => radiance,
+        };
+        (radiance * self.camera.exposure, alpha)
+    }
+}
+
+#[derive(Debug)]
+pub struct CameraBuilder {
+    look_from: Point3,
+    look_at: Point3,
+    vup: Vec3,
+    fov: f64,
+    aspect_ratio: f64,
+    aperture: f64,
+    focus_distance: f64,
+    shutter_speed: f64,
+    shutter_curve: ShutterCurve,
+    rolling_shutter: f64,
+    exposure: f64,
+    shift: (f64, f64),
+    tilt: f64,
+    swing: f64,
+    distortion: Option<LensDistortion>,
+}
+
+impl Default for CameraBuilder {
+    fn default() -> Self {
+        Self {
+            look_from: Point3::default(),
+            look_at: Point3::new(0.0, 0.0, -1.0),
+            vup: Vec3::new(0.0, 1.0, 0.0),
+            fov: 90.0,
+            aspect_ratio: 16.0 / 9.0,
+            aperture: 0.0,
+            focus_distance: 1.0,
+            shutter_speed: 0.0,
+            shutter_curve: ShutterCurve::default(),
+            rolling_shutter: 0.0,
+            exposure: 1.0,
+            shift: (0.0, 0.0),
+            tilt: 0.0,
+            swing: 0.0,
+            distortion: None,
+        }
+    }
+}
+
+impl CameraBuilder {
+    #[must_use]
+    pub const fn look_from(mut self, look_from: Point3) -> Self {
+        self.look_from = look_from;
+        self
+    }
+
+    #[must_use]
+    pub const fn look_at(mut self, look_at: Point3) -> Self {
+        self.look_at = look_at;
+        self
+    }
+
+    #[must_use]
+    pub const fn vup(mut self, vup: Vec3) -> Self {
+        self.vup = vup;
+        self
+    }
+
+    #[must_use]
+    pub fn fov(mut self, fov: f64) -> Self {
+        debug_assert!(0.0 < fov && fov <= 180.0, "fov = {}", fov);
+        self.fov = fov;
+        self
+    }
+
+    #[must_use]
+    pub fn aspect_ratio(mut self, aspect_ratio: f64) -> Self {
+        debug_assert!(aspect_ratio > 0.0, "aspect_ratio = {}", aspect_ratio);
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    #[must_use]
+    pub fn aperture(mut self, aperture: f64) -> Self {
+        debug_assert!(aperture >= 0.0, "aperture = {}", aperture);
+        self.aperture = aperture;
+        self
+    }
+
+    #[must_use]
+    pub fn focus(mut self, distance: f64) -> Self {
+        debug_assert!(distance >= 0.0, "distance = {}", distance);
+        self.focus_distance = distance;
+        self
+    }
+
+    #[must_use]
+    pub fn focus_to_look_at(self) -> Self {
+        let distance = (&self.look_at - &self.look_from).length();
+        self.focus(distance)
+    }
+
+    #[must_use]
+    pub fn shutter_speed(mut self, duration: f64) -> Self {
+        debug_assert!(duration >= 0.0, "duration = {}", duration);
+        self.shutter_speed = duration;
+        self
+    }
+
+    #[must_use]
+    pub fn shutter_curve(mut self, curve: ShutterCurve) -> Self {
+        self.shutter_curve = curve;
+        self
+    }
+
+    /// Time for sensor to read out from top row to bottom row, 0 means global shutter
+    #[must_use]
+    pub fn rolling_shutter(mut self, readout: f64) -> Self {
+        debug_assert!(readout >= 0.0, "readout = {}", readout);
+        self.rolling_shutter = readout;
+        self
+    }
+
+    #[must_use]
+    pub fn exposure(mut self, exposure: f64) -> Self {
+        debug_assert!(exposure > 0.0, "exposure = {}", exposure);
+        self.exposure = exposure;
+        self
+    }
+
+    /// Set fov, aspect ratio, aperture, shutter speed and exposure from a real world camera
+    #[must_use]
+    pub fn physical(self, camera: &PhysicalCamera) -> Self {
+        self.fov(camera.fov())
+            .aspect_ratio(camera.aspect_ratio())
+            .aperture(camera.aperture())
+            .shutter_speed(camera.shutter_time())
+            .exposure(camera.exposure())
+    }
+
+    /// Lens shift, in fraction of picture width and height
+    #[must_use]
+    pub const fn shift(mut self, horizontal: f64, vertical: f64) -> Self {
+        self.shift = (horizontal, vertical);
+        self
+    }
+
+    /// Rotate focus plane around horizontal axis, in degrees
+    #[must_use]
+    pub fn tilt(mut self, angle: f64) -> Self {
+        debug_assert!(angle.abs() < 90.0, "angle = {}", angle);
+        self.tilt = angle;
+        self
+    }
+
+    /// Rotate focus plane around vertical axis, in degrees
+    #[must_use]
+    pub fn swing(mut self, angle: f64) -> Self {
+        debug_assert!(angle.abs() < 90.0, "angle = {}", angle);
+        self.swing = angle;
+        self
+    }
+
+    #[must_use]
+    pub const fn distortion(mut self, distortion: LensDistortion) -> Self {
+        self.distortion = Some(distortion);
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Camera {
+        Camera::new(self)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::prelude::NITS_PER_UNIT;
+
+/// Settings of a real world camera.
+///
+/// Lengths(focal length, sensor size) are in millimeters, shutter time is in seconds.
+/// `scene_unit` tells how many millimeters one unit in scene stands for, default is 1000,
+/// means scene is modeled in meters.
+#[derive(Debug, Clone)]
+pub struct PhysicalCamera {
+    focal_length: f64,
+    f_number: f64,
+    sensor_width: f64,
+    sensor_height: f64,
+    shutter: f64,
+    iso: f64,
+    scene_unit: f64,
+}
+
+impl Default for PhysicalCamera {
+    fn default() -> Self {
+        Self {
+            focal_length: 50.0,
+            f_number: 16.0,
+            sensor_width: 36.0,
+            sensor_height: 24.0,
+            shutter: 1.0 / 125.0,
+            iso: 100.0,
+            scene_unit: 1000.0,
+        }
+    }
+}
+
+impl PhysicalCamera {
+    #[must_use]
+    pub fn focal_length(mut self, focal_length: f64) -> Self {
+        debug_assert!(focal_length > 0.0, "focal_length = {}", focal_length);
+        self.focal_length = focal_length;
+        self
+    }
+
+    #[must_use]
+    pub fn f_number(mut self, f_number: f64) -> Self {
+        debug_assert!(f_number > 0.0, "f_number = {}", f_number);
+        self.f_number = f_number;
+        self
+    }
+
+    #[must_use]
+    pub fn sensor(mut self, width: f64, height: f64) -> Self {
+        debug_assert!(width > 0.0, "width = {}", width);
+        debug_assert!(height > 0.0, "height = {}", height);
+        self.sensor_width = width;
+        self.sensor_height = height;
+        self
+    }
+
+    #[must_use]
+    pub fn shutter(mut self, seconds: f64) -> Self {
+        debug_assert!(seconds > 0.0, "seconds = {}", seconds);
+        self.shutter = seconds;
+        self
+    }
+
+    #[must_use]
+    pub fn iso(mut self, iso: f64) -> Self {
+        debug_assert!(iso > 0.0, "iso = {}", iso);
+        self.iso = iso;
+        self
+    }
+
+    #[must_use]
+    pub fn scene_unit(mut self, millimeters: f64) -> Self {
+        debug_assert!(millimeters > 0.0, "millimeters = {}", millimeters);
+        self.scene_unit = millimeters;
+        self
+    }
+
+    /// Vertical field of view in degrees
+    #[must_use]
+    pub fn fov(&self) -> f64 {
+        2.0 * (self.sensor_height / 2.0 / self.focal_length)
+            .atan()
+            .to_degrees()
+    }
+
+    #[must_use]
+    pub fn aspect_ratio(&self) -> f64 {
+        self.sensor_width / self.sensor_height
+    }
+
+    /// Lens opening diameter in scene unit
+    #[must_use]
+    pub fn aperture(&self) -> f64 {
+        self.focal_length / self.f_number / self.scene_unit
+    }
+
+    #[must_use]
+    pub const fn shutter_time(&self) -> f64 {
+        self.shutter
+    }
+
+    /// Exposure value at ISO 100
+    #[must_use]
+    pub fn ev100(&self) -> f64 {
+        (self.f_number * self.f_number / self.shutter * 100.0 / self.iso).log2()
+    }
+
+    /// Multiplier from radiance in the renderer(luminance in cd/m^2 divided by `NITS_PER_UNIT`)
+    /// to pixel value, the maximum luminance without saturation is mapped to 1.0.
+    #[must_use]
+    pub fn exposure(&self) -> f64 {
+        NITS_PER_UNIT / (1.2 * self.ev100().exp2())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone)]
+enum CurveType {
+    Box,
+    Triangle,
+    Custom(Vec<f64>),
+}
+
+/// How much the shutter is opened during exposure, decides time distribution of camera rays
+#[derive(Debug, Clone)]
+pub struct ShutterCurve {
+    curve_type: CurveType,
+}
+
+impl Default for ShutterCurve {
+    fn default() -> Self {
+        Self::box_curve()
+    }
+}
+
+impl ShutterCurve {
+    /// Shutter opens and closes instantly
+    #[must_use]
+    pub const fn box_curve() -> Self {
+        Self {
+            curve_type: CurveType::Box,
+        }
+    }
+
+    /// Shutter opens linearly until half of exposure time, then closes linearly
+    #[must_use]
+    pub const fn triangle() -> Self {
+        Self {
+            curve_type: CurveType::Triangle,
+        }
+    }
+
+    /// Shutter openness of evenly divided exposure time segments
+    ///
+    /// # Panics
+    ///
+    /// When values is empty, contains negative number or all values are zero
+    #[must_use]
+    pub fn custom(values: &[f64]) -> Self {
+        assert!(values.iter().all(|x| *x >= 0.0));
+        let mut sum = 0.0;
+        let mut cdf: Vec<f64> = values
+            .iter()
+            .map(|x| {
+                sum += x;
+                sum
+            })
+            .collect();
+        assert!(sum > 0.0);
+        for x in &mut cdf {
+            *x /= sum;
+        }
+        Self {
+            curve_type: CurveType::Custom(cdf),
+        }
+    }
+
+    /// Sample a time point in [0, 1] according to shutter openness
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // segment count is small enough in practice
+    pub fn sample(&self) -> f64 {
+        let x = Random::normal();
+        match &self.curve_type {
+            CurveType::Box => x,
+            CurveType::Triangle => {
+                if x < 0.5 {
+                    (x / 2.0).sqrt()
+                } else {
+                    1.0 - ((1.0 - x) / 2.0).sqrt()
+                }
+            }
+            CurveType::Custom(cdf) => {
+                let index = cdf.iter().position(|c| *c > x).unwrap_or(cdf.len() - 1);
+                let start = if index == 0 { 0.0 } else { cdf[index - 1] };
+                let offset = (x - start) / (cdf[index] - start);
+                (index as f64 + clamp(offset, 0.0..=1.0)) / cdf.len() as f64
+            }
+        }
+    }
+}
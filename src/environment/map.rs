@@ -0,0 +1,291 @@
+use {
+    crate::{environment::Environment, prelude::*},
+    image::{codecs::hdr::HdrDecoder, GenericImageView},
+    std::{
+        fmt::{Debug, Formatter},
+        fs::File,
+        io::BufReader,
+        path::Path,
+    },
+};
+
+fn rotate_y(direction: &Vec3, radian: f64) -> Vec3 {
+    let (sin, cos) = radian.sin_cos();
+    Vec3::new(
+        cos.mul_add(direction.x, sin * direction.z),
+        direction.y,
+        (-sin).mul_add(direction.x, cos * direction.z),
+    )
+}
+
+// Piecewise constant distribution over 0..n
+#[derive(Clone)]
+struct Distribution {
+    cdf: Vec<f64>,
+    total: f64,
+}
+
+impl Distribution {
+    fn new(weights: &[f64]) -> Self {
+        let mut cdf = Vec::with_capacity(weights.len());
+        let mut total = 0.0;
+        for weight in weights {
+            total += weight;
+            cdf.push(total);
+        }
+        Self { cdf, total }
+    }
+
+    // Random index by weight, and random position in 0..1 within it.
+    // Uniform when all weights are zero
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )] // count is small enough
+    fn sample(&self) -> (usize, f64) {
+        if self.total <= 0.0 {
+            let position = Random::normal() * self.cdf.len() as f64;
+            let index = (position as usize).min(self.cdf.len() - 1);
+            return (index, clamp(position - index as f64, 0.0..=1.0));
+        }
+        let target = Random::normal() * self.total;
+        let index = self
+            .cdf
+            .partition_point(|sum| *sum <= target)
+            .min(self.cdf.len() - 1);
+        let start = if index > 0 { self.cdf[index - 1] } else { 0.0 };
+        let weight = self.cdf[index] - start;
+        let offset = if weight > 0.0 {
+            (target - start) / weight
+        } else {
+            0.5
+        };
+        (index, clamp(offset, 0.0..=1.0))
+    }
+
+    // Probability of choosing the index, multiplied by count of indices.
+    // Zero when all weights are zero
+    #[allow(clippy::cast_precision_loss)] // count is small enough
+    fn density(&self, index: usize) -> f64 {
+        if self.total <= 0.0 {
+            return 0.0;
+        }
+        let start = if index > 0 { self.cdf[index - 1] } else { 0.0 };
+        (self.cdf[index] - start) / self.total * self.cdf.len() as f64
+    }
+}
+
+/// Environment light from an equirectangular image, importance sampled by luminance.
+///
+/// Radiance HDR(.hdr) files are loaded as linear radiance, other formats are decoded
+/// from sRGB by default. Image center looks at -z, top is +y
+#[derive(Clone)]
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec3>,
+    // Stored colors of low dynamic range images, kept to decode in another color space
+    encoded: Option<Vec<Color>>,
+    intensity: f64,
+    rotation: f64,
+    rows: Distribution,
+    columns: Vec<Distribution>,
+}
+
+impl Debug for EnvironmentMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "EnvironmentMap {{ {}x{}, intensity: {}, rotation: {} }}",
+            self.width, self.height, self.intensity, self.rotation
+        ))
+    }
+}
+
+impl EnvironmentMap {
+    /// # Errors
+    ///
+    /// When load image failed
+    pub fn new<P: AsRef<Path>>(p: P) -> Result<Self, String> {
+        let path = p.as_ref();
+        if matches!(path.extension(), Some(ext) if ext.eq_ignore_ascii_case("hdr")) {
+            let file = File::open(path).map_err(|e| e.to_string())?;
+            let decoder = HdrDecoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+            let metadata = decoder.metadata();
+            let pixels = decoder
+                .read_image_hdr()
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|p| Vec3::new(f64::from(p[0]), f64::from(p[1]), f64::from(p[2])))
+                .collect();
+            Ok(Self::from_pixels(
+                metadata.width as usize,
+                metadata.height as usize,
+                pixels,
+            ))
+        } else {
+            let img = image::open(path).map_err(|e| e.to_string())?;
+            let encoded = img
+                .to_rgb8()
+                .pixels()
+                .map(|p| Color::new_int(p[0], p[1], p[2]))
+                .collect();
+            Ok(Self::decode(
+                img.width() as usize,
+                img.height() as usize,
+                encoded,
+                ColorSpace::SRGB,
+            ))
+        }
+    }
+
+    fn decode(width: usize, height: usize, encoded: Vec<Color>, color_space: ColorSpace) -> Self {
+        let pixels = encoded
+            .iter()
+            .map(|color| color_space.decode(color).into())
+            .collect();
+        Self {
+            encoded: Some(encoded),
+            ..Self::from_pixels(width, height, pixels)
+        }
+    }
+
+    /// Map from linear radiance of pixels, row by row from the top
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // image size is small enough
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Vec3>) -> Self {
+        debug_assert!(width > 0 && height > 0, "size = {}x{}", width, height);
+        debug_assert!(
+            pixels.len() == width * height,
+            "pixels.len() = {}",
+            pixels.len()
+        );
+        // Rows near poles cover smaller solid angle
+        let columns: Vec<_> = pixels
+            .chunks(width)
+            .enumerate()
+            .map(|(row, pixels)| {
+                let latitude = ((row as f64 + 0.5) / height as f64 - 0.5) * PI;
+                let weights: Vec<_> = pixels
+                    .iter()
+                    .map(|p| {
+                        0.0722f64.mul_add(p.z, 0.2126f64.mul_add(p.x, 0.7152 * p.y))
+                            * latitude.cos()
+                    })
+                    .collect();
+                Distribution::new(&weights)
+            })
+            .collect();
+        let rows = Distribution::new(&columns.iter().map(|c| c.total).collect::<Vec<_>>());
+        Self {
+            width,
+            height,
+            pixels,
+            encoded: None,
+            intensity: 1.0,
+            rotation: 0.0,
+            rows,
+            columns,
+        }
+    }
+
+    #[must_use]
+    pub fn intensity(mut self, intensity: f64) -> Self {
+        debug_assert!(intensity >= 0.0, "intensity = {}", intensity);
+        self.intensity = intensity;
+        self
+    }
+
+    /// Color space pixels of low dynamic range images are stored in, default is sRGB.
+    /// Radiance HDR and maps from pixels are linear and not changed
+    #[must_use]
+    pub fn color_space(self, color_space: ColorSpace) -> Self {
+        match self.encoded {
+            Some(encoded) => Self {
+                intensity: self.intensity,
+                rotation: self.rotation,
+                ..Self::decode(self.width, self.height, encoded, color_space)
+            },
+            None => self,
+        }
+    }
+
+    /// Rotate around y axis by the angle in degrees
+    #[must_use]
+    pub const fn rotation(mut self, angle: f64) -> Self {
+        self.rotation = angle.to_radians();
+        self
+    }
+
+    // Pixel the direction falls in, and cos of its latitude
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)] // u v are in 0..1
+    fn pixel(&self, direction: &Vec3) -> (usize, usize, f64) {
+        let direction = rotate_y(&direction.unit(), -self.rotation);
+        let phi = (-direction.z).atan2(direction.x);
+        let theta = clamp(direction.y, -1.0..=1.0).asin();
+        let u = phi / 2.0 / PI + 0.5;
+        let v = 0.5 - theta / PI;
+        let column = ((u * self.width as f64) as usize).min(self.width - 1);
+        let row = ((v * self.height as f64) as usize).min(self.height - 1);
+        (row, column, theta.cos())
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn radiance(&self, direction: &Vec3) -> Vec3 {
+        let (row, column, _) = self.pixel(direction);
+        &self.pixels[row * self.width + column] * self.intensity
+    }
+
+    fn is_light(&self) -> bool {
+        self.rows.total > 0.0
+    }
+
+    // Density in image is piecewise constant, and solid angle of unit image area is
+    // 2 * PI^2 * cos(latitude)
+    fn pdf_value(&self, direction: &Vec3) -> f64 {
+        let (row, column, cos) = self.pixel(direction);
+        if cos <= 0.0 {
+            return 0.0;
+        }
+        let density = self.rows.density(row) * self.columns[row].density(column);
+        density / (2.0 * PI * PI * cos)
+    }
+
+    #[allow(clippy::cast_precision_loss)] // image size is small enough
+    fn random_direction(&self) -> Vec3 {
+        let (row, v) = self.rows.sample();
+        let (column, u) = self.columns[row].sample();
+        let u = (column as f64 + u) / self.width as f64;
+        let v = (row as f64 + v) / self.height as f64;
+        let phi = (u - 0.5) * 2.0 * PI;
+        let theta = (0.5 - v) * PI;
+        let direction = Vec3::new(
+            theta.cos() * phi.cos(),
+            theta.sin(),
+            -theta.cos() * phi.sin(),
+        );
+        rotate_y(&direction, self.rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pdf_of_black_row_is_zero() {
+        let mut pixels = vec![Vec3::new(1.0, 1.0, 1.0); 4];
+        pixels.extend(vec![Vec3::default(); 4]);
+        let map = EnvironmentMap::from_pixels(4, 2, pixels);
+
+        let down = map.pdf_value(&Vec3::new(0.0, -1.0, -1.0));
+        assert!(down.abs() < f64::EPSILON, "pdf of black row = {}", down);
+        let up = map.pdf_value(&Vec3::new(0.0, 1.0, -1.0));
+        assert!(up.is_finite() && up > 0.0, "pdf of lit row = {}", up);
+        for _ in 0..100 {
+            let direction = map.random_direction();
+            assert!(direction.y >= 0.0, "sampled {:?} in black row", direction);
+        }
+    }
+}
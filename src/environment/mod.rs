@@ -0,0 +1,45 @@
+pub(crate) mod map;
+pub(crate) mod sky;
+
+pub use {map::EnvironmentMap, sky::Sky};
+
+use {crate::prelude::*, std::sync::Arc};
+
+/// Light coming from infinitely far away in every direction, seen by rays escaping the scene
+#[allow(unused_variables)]
+pub trait Environment: Send + Sync {
+    /// Radiance coming from the direction
+    fn radiance(&self, direction: &Vec3) -> Vec3;
+    /// Whether this environment can be sampled by `random_direction` for direct lighting
+    fn is_light(&self) -> bool {
+        false
+    }
+    /// Probability density(in solid angle) of `random_direction` generating the direction,
+    /// default is uniform on the sphere
+    fn pdf_value(&self, direction: &Vec3) -> f64 {
+        0.25 / PI
+    }
+    /// Random unit direction, more likely to be the bright parts. Default is uniform on the
+    /// sphere
+    fn random_direction(&self) -> Vec3 {
+        Vec3::random_unit()
+    }
+}
+
+impl<E: Environment + ?Sized> Environment for Arc<E> {
+    fn radiance(&self, direction: &Vec3) -> Vec3 {
+        self.as_ref().radiance(direction)
+    }
+
+    fn is_light(&self) -> bool {
+        self.as_ref().is_light()
+    }
+
+    fn pdf_value(&self, direction: &Vec3) -> f64 {
+        self.as_ref().pdf_value(direction)
+    }
+
+    fn random_direction(&self) -> Vec3 {
+        self.as_ref().random_direction()
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{environment::Environment, prelude::*};
+
+// Angular radius of the sun seen from the earth
+const SUN_RADIUS: f64 = 0.2665 * PI / 180.0;
+// Illuminance of the sun out of the atmosphere, in lux
+const SUN_ILLUMINANCE: f64 = 128_000.0;
+// Probability of sampling the sun disk instead of the whole sky
+const SUN_PROBABILITY: f64 = 0.5;
+
+/// Coefficients of Perez sky model
+#[derive(Debug, Clone)]
+struct Perez([f64; 5]);
+
+impl Perez {
+    // Relative luminance of direction with zenith angle theta and angle to the sun gamma
+    fn at(&self, cos_theta: f64, gamma: f64) -> f64 {
+        let [a, b, c, d, e] = self.0;
+        let cos_gamma = gamma.cos();
+        a.mul_add((b / cos_theta).exp(), 1.0)
+            * e.mul_add(cos_gamma * cos_gamma, c.mul_add((d * gamma).exp(), 1.0))
+    }
+}
+
+/// Daylight sky by Preetham's analytic model, with a sun disk which can be sampled as a light.
+///
+/// Radiance is physical luminance divided by `NITS_PER_UNIT` then times the intensity,
+/// so clear sky is around 1 and the sun is much brighter. Sky below horizon repeats the horizon
+#[derive(Debug, Clone)]
+pub struct Sky {
+    sun: Vec3,
+    turbidity: f64,
+    intensity: f64,
+}
+
+impl Sky {
+    /// Sun at the elevation above horizon and the azimuth from -z towards +x, in degrees
+    #[must_use]
+    pub fn new(elevation: f64, azimuth: f64) -> Self {
+        debug_assert!(
+            (0.0..=90.0).contains(&elevation),
+            "elevation = {}",
+            elevation
+        );
+        let (elevation, azimuth) = (elevation.to_radians(), azimuth.to_radians());
+        Self {
+            sun: Vec3::new(
+                elevation.cos() * azimuth.sin(),
+                elevation.sin(),
+                -elevation.cos() * azimuth.cos(),
+            ),
+            turbidity: 3.0,
+            intensity: 1.0,
+        }
+    }
+
+    /// Haziness of the air, 2 is very clear, 10 is hazy, default is 3
+    #[must_use]
+    pub fn turbidity(mut self, turbidity: f64) -> Self {
+        debug_assert!(
+            (1.7..=10.0).contains(&turbidity),
+            "turbidity = {}",
+            turbidity
+        );
+        self.turbidity = turbidity;
+        self
+    }
+
+    /// Scale of radiance, default is 1
+    #[must_use]
+    pub fn intensity(mut self, intensity: f64) -> Self {
+        debug_assert!(intensity >= 0.0, "intensity = {}", intensity);
+        self.intensity = intensity;
+        self
+    }
+
+    // Perez coefficients of x, y chromaticity and Y luminance
+    const fn perez(&self) -> [Perez; 3] {
+        let t = self.turbidity;
+        [
+            Perez([
+                (-0.0193f64).mul_add(t, -0.2592),
+                (-0.0665f64).mul_add(t, 0.0008),
+                (-0.0004f64).mul_add(t, 0.2125),
+                (-0.0641f64).mul_add(t, -0.8989),
+                (-0.0033f64).mul_add(t, 0.0452),
+            ]),
+            Perez([
+                (-0.0167f64).mul_add(t, -0.2608),
+                (-0.0950f64).mul_add(t, 0.0092),
+                (-0.0079f64).mul_add(t, 0.2102),
+                (-0.0441f64).mul_add(t, -1.6537),
+                (-0.0109f64).mul_add(t, 0.0529),
+            ]),
+            Perez([
+                0.1787f64.mul_add(t, -1.4630),
+                (-0.3554f64).mul_add(t, 0.4275),
+                (-0.0227f64).mul_add(t, 5.3251),
+                0.1206f64.mul_add(t, -2.5771),
+                (-0.0670f64).mul_add(t, 0.3703),
+            ]),
+        ]
+    }
+
+    // Chromaticity x, y and luminance Y(in kcd/m^2) at zenith
+    fn zenith(&self) -> [f64; 3] {
+        let t = self.turbidity;
+        let theta = clamp(self.sun.y, -1.0..=1.0).acos();
+        let cubic = |a: f64, b: f64, c: f64, d: f64| {
+            a.mul_add(theta, b).mul_add(theta, c).mul_add(theta, d)
+        };
+        let x = (t * t).mul_add(
+            cubic(0.00166, -0.00375, 0.00209, 0.0),
+            t.mul_add(
+                cubic(-0.02903, 0.06377, -0.03202, 0.00394),
+                cubic(0.11693, -0.21196, 0.06052, 0.25886),
+            ),
+        );
+        let y = (t * t).mul_add(
+            cubic(0.00275, -0.00610, 0.00317, 0.0),
+            t.mul_add(
+                cubic(-0.04214, 0.08970, -0.04153, 0.00516),
+                cubic(0.15346, -0.26756, 0.06670, 0.26688),
+            ),
+        );
+        let chi = (4.0 / 9.0 - t / 120.0) * 2.0f64.mul_add(-theta, PI);
+        let luminance = 4.0453f64
+            .mul_add(t, -4.9710)
+            .mul_add(chi.tan(), (-0.2155f64).mul_add(t, 2.4192));
+        [x, y, luminance]
+    }
+
+    fn sky(&self, direction: &Vec3) -> Vec3 {
+        let cos_theta = direction.y.max(0.01);
+        let gamma = clamp(direction.dot(&self.sun), -1.0..=1.0).acos();
+        let sun_theta = clamp(self.sun.y, -1.0..=1.0).acos();
+        let zenith = self.zenith();
+        let mut xy_luminance = [0.0; 3];
+        for (i, perez) in self.perez().iter().enumerate() {
+            xy_luminance[i] = zenith[i] * perez.at(cos_theta, gamma) / perez.at(1.0, sun_theta);
+        }
+        let [x, y, luminance] = xy_luminance;
+        let xyz = Vec3::new(x / y, 1.0, (1.0 - x - y) / y) * luminance;
+        let rgb = Spectrum::xyz_to_rgb(&xyz) * (1000.0 / NITS_PER_UNIT * self.intensity);
+        Vec3::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+    }
+
+    // Sunlight attenuated by Rayleigh and aerosol scattering, red green and blue are
+    // taken at 680nm, 550nm and 440nm
+    fn sun_radiance(&self) -> Vec3 {
+        let zenith = clamp(self.sun.y, -1.0..=1.0).acos().to_degrees();
+        let air_mass =
+            1.0 / 0.15f64.mul_add((93.885 - zenith).powf(-1.253), zenith.to_radians().cos());
+        let beta = 0.04608f64.mul_add(self.turbidity, -0.04586);
+        let transmittance = |micrometers: f64| {
+            let depth =
+                0.008_735f64.mul_add(micrometers.powf(-4.08), beta * micrometers.powf(-1.3));
+            (-depth * air_mass).exp()
+        };
+        let solid_angle = 2.0 * PI * (1.0 - SUN_RADIUS.cos());
+        let luminance = SUN_ILLUMINANCE / solid_angle / NITS_PER_UNIT * self.intensity;
+        let color = Spectrum::blackbody(5778.0);
+        Vec3::new(
+            color.x * transmittance(0.68),
+            color.y * transmittance(0.55),
+            color.z * transmittance(0.44),
+        ) * luminance
+    }
+}
+
+impl Environment for Sky {
+    fn radiance(&self, direction: &Vec3) -> Vec3 {
+        let direction = direction.unit();
+        let sky = self.sky(&direction);
+        if direction.dot(&self.sun) >= SUN_RADIUS.cos() {
+            sky + self.sun_radiance()
+        } else {
+            sky
+        }
+    }
+
+    fn is_light(&self) -> bool {
+        true
+    }
+
+    // Mixture of the sun cone and the whole sphere, so sky is covered too
+    fn pdf_value(&self, direction: &Vec3) -> f64 {
+        let sphere = 0.25 / PI;
+        let cone = if direction.unit().dot(&self.sun) >= SUN_RADIUS.cos() {
+            1.0 / (2.0 * PI * (1.0 - SUN_RADIUS.cos()))
+        } else {
+            0.0
+        };
+        SUN_PROBABILITY.mul_add(cone, (1.0 - SUN_PROBABILITY) * sphere)
+    }
+
+    fn random_direction(&self) -> Vec3 {
+        if Random::normal() < SUN_PROBABILITY {
+            ONB::new(&self.sun).local(&Vec3::random_in_cone(SUN_RADIUS.cos()))
+        } else {
+            Vec3::random_unit()
+        }
+    }
+}
@@ -130,4 +130,26 @@ impl Hittable for BVH {
     fn bbox(&self, _time_limit: Range<f64>) -> Option<AABB> {
         self.bbox.clone()
     }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        let Some(bbox) = self.bbox.as_ref() else {
+            return 1.0;
+        };
+        if !bbox.hit(ray, unit_limit.clone()) {
+            return 1.0;
+        }
+
+        let left = self
+            .left
+            .as_ref()
+            .map_or(1.0, |left| left.transmittance(ray, unit_limit.clone()));
+        // Fully blocked by left, no need to check right
+        if left <= 0.0 {
+            return 0.0;
+        }
+        left * self
+            .right
+            .as_ref()
+            .map_or(1.0, |right| right.transmittance(ray, unit_limit))
+    }
 }
@@ -1,6 +1,6 @@
 use {
     crate::{
-        hittable::{Hittable, HitRecord},
+        hittable::{HitRecord, Hittable},
         prelude::*,
     },
     std::{
@@ -12,6 +12,8 @@ use {
 #[derive(Default)]
 pub struct HittableList {
     objects: Vec<Box<dyn Hittable>>,
+    // Indices of objects which are lights
+    lights: Vec<usize>,
 }
 
 impl Debug for HittableList {
@@ -26,17 +28,20 @@ impl Debug for HittableList {
 impl HittableList {
     pub fn add<G: Hittable + 'static>(&mut self, object: G) -> &mut Self {
         let object: Box<dyn Hittable> = Box::new(object);
-        self.objects.push(object);
-        self
+        self.add_ref(object)
     }
 
     pub fn add_ref(&mut self, object: Box<dyn Hittable>) -> &mut Self {
+        if object.is_light() {
+            self.lights.push(self.objects.len());
+        }
         self.objects.push(object);
         self
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.lights.clear();
     }
 
     #[must_use]
@@ -67,4 +72,58 @@ impl Hittable for HittableList {
 
         result
     }
+
+    fn is_light(&self) -> bool {
+        !self.lights.is_empty()
+    }
+
+    #[allow(clippy::cast_precision_loss)] // light count is small enough
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .lights
+            .iter()
+            .map(|index| self.objects[*index].pdf_value(ray))
+            .sum();
+        sum / self.lights.len() as f64
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        if self.lights.is_empty() {
+            return None;
+        }
+        self.objects[*Random::choose(&self.lights)].random_direction(origin, time)
+    }
+
+    // Only lights are counted, same as other light sampling methods
+    fn area(&self) -> f64 {
+        self.lights.iter().map(|index| self.objects[*index].area()).sum()
+    }
+
+    // Choose light by area, so the point is uniformly distributed on all lights
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        let mut left = Random::normal() * self.area();
+        for index in &self.lights {
+            let area = self.objects[*index].area();
+            if area > 0.0 && left < area {
+                return self.objects[*index].random_point(time);
+            }
+            left -= area;
+        }
+        self.lights
+            .iter()
+            .rev()
+            .map(|index| &self.objects[*index])
+            .find(|object| object.area() > 0.0)?
+            .random_point(time)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        self.objects
+            .iter()
+            .map(|object| object.transmittance(ray, unit_limit.clone()))
+            .product()
+    }
 }
@@ -5,5 +5,5 @@ pub(crate) mod world;
 pub use {
     bvh::BVH,
     list::HittableList,
-    world::{default_background as world_default_background, World},
+    world::{default_background as world_default_background, PickRecord, World},
 };
@@ -1,14 +1,21 @@
 use {
     crate::{
+        environment::Environment,
         hittable::{
             collection::{HittableList, BVH},
+            medium::Atmosphere,
             HitRecord, Hittable,
         },
+        material::PhaseFunction,
         prelude::*,
     },
     std::{
         fmt::{Debug, Formatter},
         ops::Range,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
     },
 };
 
@@ -16,12 +23,82 @@ use {
 pub fn default_background(ray: &Ray) -> Color {
     let unit = ray.direction.unit();
     let t = 0.5 * (unit.y + 1.0);
-    Color::new(1.0, 1.0, 1.0).gradient(&Color::new(0.5, 0.7, 1.0), t)
+    Color::new(1.0, 1.0, 1.0).lerp(&Color::new(0.5, 0.7, 1.0), t)
+}
+
+// Stamp top level object index into hit record, so user can know what is hit
+struct IdentifiedObject {
+    id: usize,
+    object: Arc<dyn Hittable>,
+}
+
+impl Hittable for IdentifiedObject {
+    fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
+        self.object.hit(ray, unit_limit).map(|mut record| {
+            record.object = Some(self.id);
+            record
+        })
+    }
+
+    fn bbox(&self, time_limit: Range<f64>) -> Option<AABB> {
+        self.object.bbox(time_limit)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        self.object.transmittance(ray, unit_limit)
+    }
+
+    fn is_light(&self) -> bool {
+        self.object.is_light()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        self.object.pdf_value(ray)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        self.object.random_direction(origin, time)
+    }
+
+    fn area(&self) -> f64 {
+        self.object.area()
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        self.object.random_point(time).map(|mut record| {
+            record.object = Some(self.id);
+            record
+        })
+    }
+}
+
+/// Information about the first object a ray hits
+#[derive(Debug, Clone)]
+pub struct PickRecord {
+    /// Index of the object in the list which the world is created from,
+    /// None if the hit object can't be identified
+    pub object: Option<usize>,
+    pub point: Point3,
+    pub normal: Vec3,
+    pub distance: f64,
+    pub u: f64,
+    pub v: f64,
+}
+
+// Identity of worlds, changed on every modification
+fn next_world_id() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
 }
 
 pub struct World {
+    id: usize,
     bvh: BVH,
+    lights: HittableList,
+    light_ids: Vec<usize>,
     bg_func: Box<dyn Fn(&Ray) -> Color + Send + Sync>,
+    atmosphere: Option<Box<dyn Hittable>>,
+    environment: Option<Box<dyn Environment>>,
 }
 
 impl Debug for World {
@@ -33,9 +110,28 @@ impl Debug for World {
 impl World {
     #[must_use]
     pub fn new(list: HittableList, time_range: Range<f64>) -> Self {
+        let mut identified = HittableList::default();
+        let mut lights = HittableList::default();
+        let mut light_ids = vec![];
+        for (id, object) in list.into_objects().into_iter().enumerate() {
+            let object: Arc<dyn Hittable> = Arc::from(object);
+            if object.is_light() {
+                lights.add(IdentifiedObject {
+                    id,
+                    object: Arc::clone(&object),
+                });
+                light_ids.push(id);
+            }
+            identified.add(IdentifiedObject { id, object });
+        }
         Self {
-            bvh: BVH::new(list, time_range),
+            id: next_world_id(),
+            bvh: BVH::new(identified, time_range),
+            lights,
+            light_ids,
             bg_func: Box::new(default_background),
+            atmosphere: None,
+            environment: None,
         }
     }
 
@@ -44,21 +140,223 @@ impl World {
         F: Fn(&Ray) -> Color + Send + Sync + 'static,
     {
         self.bg_func = Box::new(f);
+        self.id = next_world_id();
     }
 
+    /// Fill the whole scene with the medium
+    pub fn set_atmosphere<P: PhaseFunction + 'static>(&mut self, atmosphere: Atmosphere<P>) {
+        self.atmosphere = Some(Box::new(atmosphere));
+        self.id = next_world_id();
+    }
+
+    /// Use the environment instead of background function, and sample it as a light
+    /// if it can be sampled
+    pub fn set_environment<E: Environment + 'static>(&mut self, environment: E) {
+        self.environment = Some(Box::new(environment));
+        self.id = next_world_id();
+    }
+
+    /// Unique among all worlds and changed when the world is modified, so data
+    /// prepared for a world can be checked against it
+    #[must_use]
+    pub const fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Radiance of rays escaping the scene, not clamped when it's from environment
     #[must_use]
     pub fn background(&self, ray: &Ray) -> Color {
-        let f = &self.bg_func;
-        f(ray)
+        self.environment.as_ref().map_or_else(
+            || {
+                let f = &self.bg_func;
+                f(ray)
+            },
+            |environment| environment.radiance(&ray.direction).into(),
+        )
+    }
+
+    /// Whether there are top level emissive objects or environment which can be sampled directly
+    #[must_use]
+    pub fn has_lights(&self) -> bool {
+        self.lights.is_light() || self.is_sampled_environment()
+    }
+
+    /// Whether the environment is one of the lights sampled by `light_direction`
+    #[must_use]
+    pub fn is_sampled_environment(&self) -> bool {
+        matches!(&self.environment, Some(environment) if environment.is_light())
+    }
+
+    // Probability of sampling environment instead of objects in `light_direction`
+    fn environment_probability(&self) -> f64 {
+        match (self.lights.is_light(), self.is_sampled_environment()) {
+            (true, true) => 0.5,
+            (false, true) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Whether the hit object is one of the lights sampled by `light_direction`
+    #[must_use]
+    pub fn is_sampled_light(&self, record: &HitRecord<'_>) -> bool {
+        matches!(record.object, Some(id) if self.light_ids.binary_search(&id).is_ok())
+    }
+
+    /// Random direction from origin to one of the lights, None if there is no light
+    #[must_use]
+    pub fn light_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        match &self.environment {
+            Some(environment) if Random::normal() < self.environment_probability() => {
+                Some(environment.random_direction())
+            }
+            _ => self.lights.random_direction(origin, time),
+        }
+    }
+
+    /// Probability density of `light_direction` generating the ray's direction
+    #[must_use]
+    pub fn light_pdf(&self, ray: &Ray) -> f64 {
+        let p = self.environment_probability();
+        let mut pdf = 0.0;
+        if let (true, Some(environment)) = (p > 0.0, &self.environment) {
+            pdf += p * environment.pdf_value(&ray.direction);
+        }
+        if p < 1.0 {
+            pdf += (1.0 - p) * self.lights.pdf_value(ray);
+        }
+        pdf
+    }
+
+    // Radiance from lights sampled by `light_direction` along the ray, attenuated by objects
+    // and media in front of them, and transmittance to the farthest one reached. Lights casting
+    // no shadow let the ray go on to lights behind them. None if it reaches no emitting light
+    fn sampled_light(&self, ray: &Ray) -> Option<(Vec3, f64)> {
+        let shadow = ray.clone().kind(RayKind::Shadow);
+        let lookup = ray.clone().kind(RayKind::Light);
+        let mut found: Option<(Vec3, f64)> = None;
+        let mut passed = vec![];
+        let mut start = 0.001;
+        while let Some(record) = self.lights.hit(&lookup, start..f64::INFINITY) {
+            start = record.unit + 0.001;
+            // Far side of a light already passed
+            if passed.contains(&record.object) {
+                continue;
+            }
+            let transmittance = self.transmittance(&shadow, 0.001..record.unit - 0.001);
+            if let Some(emitted) = record.material.emitted(record.u, record.v, &record.point) {
+                let radiance = found.map_or_else(Vec3::default, |(radiance, _)| radiance);
+                found = Some((radiance + emitted * transmittance, transmittance));
+            }
+            let unit = record.unit;
+            let blocking = self.lights.hit(&shadow, unit - 0.001..unit + 0.001);
+            if transmittance <= 0.0 || blocking.is_some() {
+                return found;
+            }
+            passed.push(record.object);
+        }
+        if self.is_sampled_environment() {
+            let transmittance = self.transmittance(&shadow, 0.001..f64::INFINITY);
+            let radiance = found.map_or_else(Vec3::default, |(radiance, _)| radiance);
+            let background: Vec3 = self.background(ray).into();
+            found = Some((radiance + background * transmittance, transmittance));
+        }
+        found
+    }
+
+    /// Whether the light reached by the shadow ray can also be found by rays scattered by
+    /// materials, so bsdf sampling has chance to find it. False for lights hidden from reflection
+    #[must_use]
+    pub fn is_reflected_light(&self, shadow: &Ray) -> bool {
+        let lookup = shadow.clone().kind(RayKind::Light);
+        let Some(light) = self.lights.hit(&lookup, 0.001..f64::INFINITY) else {
+            return true;
+        };
+        let reflection = shadow.clone().kind(RayKind::Reflection);
+        self.lights
+            .hit(&reflection, 0.001..f64::INFINITY)
+            .is_some_and(|record| (record.unit - light.unit).abs() < 1e-9)
+    }
+
+    /// Fraction of light from lights sampled by `light_direction` arriving along the ray,
+    /// after passing objects and media in front of them. Zero if it doesn't reach those lights
+    #[must_use]
+    pub fn light_transmittance(&self, ray: &Ray) -> f64 {
+        self.sampled_light(ray)
+            .map_or(0.0, |(_, transmittance)| transmittance)
+    }
+
+    /// Radiance arriving along the ray from lights sampled by `light_direction`, attenuated by
+    /// media in front of them. None if it's blocked or doesn't reach those lights
+    #[must_use]
+    pub fn light_radiance(&self, ray: &Ray) -> Option<Vec3> {
+        self.sampled_light(ray)
+            .map(|(radiance, _)| radiance)
+            .filter(|radiance| *radiance != Vec3::default())
+    }
+
+    /// Random point uniformly distributed on all lights, with its probability density by area.
+    ///
+    /// None if there is no light or lights can't be sampled by area
+    #[must_use]
+    pub fn light_point(&self, time: f64) -> Option<(HitRecord<'_>, f64)> {
+        let area = self.lights.area();
+        if area > 0.0 {
+            Some((self.lights.random_point(time)?, 1.0 / area))
+        } else {
+            None
+        }
+    }
+
+    /// Probability density of `light_point` generating the hit point
+    #[must_use]
+    pub fn light_point_pdf(&self, record: &HitRecord<'_>) -> f64 {
+        let area = self.lights.area();
+        if area > 0.0 && self.is_sampled_light(record) {
+            1.0 / area
+        } else {
+            0.0
+        }
+    }
+
+    /// Nearest object hit by the ray, atmosphere is ignored
+    #[must_use]
+    pub fn hit_object(&self, ray: &Ray) -> Option<HitRecord<'_>> {
+        self.bvh.hit(ray, 0.001..f64::INFINITY)
+    }
+
+    /// Objects only, atmosphere is ignored
+    #[must_use]
+    pub fn pick(&self, ray: &Ray) -> Option<PickRecord> {
+        self.hit_object(ray).map(|record| PickRecord {
+            object: record.object,
+            distance: record.unit * ray.direction.length(),
+            point: record.point,
+            normal: record.normal,
+            u: record.u,
+            v: record.v,
+        })
     }
 }
 
 impl Hittable for World {
     fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
-        self.bvh.hit(ray, unit_limit)
+        let object = self.bvh.hit(ray, unit_limit.clone());
+        match &self.atmosphere {
+            Some(atmosphere) => {
+                let end = object.as_ref().map_or(unit_limit.end, |record| record.unit);
+                atmosphere.hit(ray, unit_limit.start..end).or(object)
+            }
+            None => object,
+        }
     }
 
     fn bbox(&self, time_limit: Range<f64>) -> Option<AABB> {
         self.bvh.bbox(time_limit)
     }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        self.atmosphere.as_ref().map_or(1.0, |atmosphere| {
+            atmosphere.transmittance(ray, unit_limit.clone())
+        }) * self.bvh.transmittance(ray, unit_limit)
+    }
 }
@@ -1,6 +1,6 @@
 use {
     crate::{
-        hittable::{collection::HittableList, AARect, AARectMetrics, Hittable, HitRecord},
+        hittable::{collection::HittableList, AARect, AARectMetrics, HitRecord, Hittable},
         material::Material,
         prelude::*,
     },
@@ -102,12 +102,52 @@ impl<M: Material + 'static> Carton<M> {
     }
 }
 
+impl<M> Carton<M> {
+    // Faces share normals with the opposite ones, find the one pointing out of the box
+    fn outward_normal(&self, point: &Point3, normal: &Vec3) -> Vec3 {
+        let axis = (0..3).find(|axis| normal[*axis] != 0.0).unwrap_or_default();
+        let mut outward = Vec3::default();
+        outward[axis] = if point[axis] * 2.0 < self.point_min[axis] + self.point_max[axis] {
+            -1.0
+        } else {
+            1.0
+        };
+        outward
+    }
+}
+
 impl<M: Material> Hittable for Carton<M> {
     fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
-        self.faces.hit(ray, unit_limit)
+        self.faces.hit(ray, unit_limit).map(|mut record| {
+            let outward = self.outward_normal(&record.point, &record.normal);
+            record.outside = ray.direction.dot(&outward) < 0.0;
+            record
+        })
     }
 
     fn bbox(&self, time_limit: Range<f64>) -> Option<AABB> {
         self.faces.bbox(time_limit)
     }
+
+    fn is_light(&self) -> bool {
+        self.faces.is_light()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        self.faces.pdf_value(ray)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        self.faces.random_direction(origin, time)
+    }
+
+    fn area(&self) -> f64 {
+        self.faces.area()
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        let mut record = self.faces.random_point(time)?;
+        record.normal = self.outward_normal(&record.point, &record.normal);
+        Some(record)
+    }
 }
@@ -1,6 +1,6 @@
 use {
     crate::{
-        hittable::{Hittable, HitRecord},
+        hittable::{HitRecord, Hittable},
         material::Material,
         prelude::*,
     },
@@ -121,4 +121,37 @@ impl<M: Material> Hittable for AARect<M> {
 
         Some(AABB::new(p0, p1))
     }
+
+    fn is_light(&self) -> bool {
+        self.material.emissive()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        self.hit(ray, 0.001..f64::INFINITY).map_or(0.0, |record| {
+            let length_squared = ray.direction.length_squared();
+            let distance_squared = record.unit * record.unit * length_squared;
+            let cos = (ray.direction[self.axis.2] / length_squared.sqrt()).abs();
+            distance_squared / (cos * self.metrics.a_len * self.metrics.b_len)
+        })
+    }
+
+    fn random_direction(&self, origin: &Point3, _time: f64) -> Option<Vec3> {
+        let mut point = Point3::default();
+        point[self.axis.0] = Random::range(self.metrics.a0..self.metrics.a1);
+        point[self.axis.1] = Random::range(self.metrics.b0..self.metrics.b1);
+        point[self.axis.2] = self.metrics.k;
+        Some(point - origin)
+    }
+
+    fn area(&self) -> f64 {
+        self.metrics.a_len * self.metrics.b_len
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        let mut point = Point3::default();
+        point[self.axis.0] = Random::range(self.metrics.a0..self.metrics.a1);
+        point[self.axis.1] = Random::range(self.metrics.b0..self.metrics.b1);
+        point[self.axis.2] = self.metrics.k;
+        Some(HitRecord::on_surface(self, point, time))
+    }
 }
@@ -131,4 +131,40 @@ impl<M: Material> Hittable for Sphere<M> {
             },
         )
     }
+
+    fn is_light(&self) -> bool {
+        self.material.emissive()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        let distance_squared = (self.center_at(ray.departure_time) - &ray.origin).length_squared();
+        if distance_squared <= self.radius_squared {
+            return 0.25 / PI;
+        }
+        if self.hit(ray, 0.001..f64::INFINITY).is_none() {
+            return 0.0;
+        }
+        let cos_theta_max = (1.0 - self.radius_squared / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        let direction = self.center_at(time) - origin;
+        let distance_squared = direction.length_squared();
+        if distance_squared <= self.radius_squared {
+            return Some(Vec3::random_unit());
+        }
+        let cos_theta_max = (1.0 - self.radius_squared / distance_squared).sqrt();
+        Some(ONB::new(&direction).local(&Vec3::random_in_cone(cos_theta_max)))
+    }
+
+    fn area(&self) -> f64 {
+        4.0 * PI * self.radius_squared
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        let point = self.center_at(time) + self.radius * Vec3::random_unit();
+        Some(HitRecord::on_surface(self, point, time))
+    }
 }
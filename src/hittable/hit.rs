@@ -3,9 +3,11 @@ use {
     std::{
         fmt::{Debug, Formatter},
         ops::Range,
+        sync::Arc,
     },
 };
 
+#[derive(Clone)]
 pub struct HitRecord<'m> {
     pub point: Point3,
     pub normal: Vec3,
@@ -14,6 +16,7 @@ pub struct HitRecord<'m> {
     pub u: f64,
     pub v: f64,
     pub outside: bool,
+    pub object: Option<usize>,
 }
 
 impl Debug for HitRecord<'_> {
@@ -43,8 +46,18 @@ impl<'m> HitRecord<'m> {
             u,
             v,
             outside,
+            object: None,
         }
     }
+
+    /// Record of a known point on object's surface, normal points outward
+    pub fn on_surface<G: Hittable>(object: &'m G, point: Point3, time: f64) -> Self {
+        let normal = object.normal(&point);
+        let ray = Ray::new(&point + &normal, -normal, time).kind(RayKind::Shadow);
+        let mut record = Self::new(&ray, object, 1.0);
+        record.point = point;
+        record
+    }
 }
 
 #[allow(unused_variables)]
@@ -69,4 +82,69 @@ pub trait Hittable: Send + Sync {
     }
     fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>>;
     fn bbox(&self, time_limit: Range<f64>) -> Option<AABB>;
+    /// Whether this object emits light and can be sampled by `random_direction`
+    fn is_light(&self) -> bool {
+        false
+    }
+    /// Probability density(in solid angle) of `random_direction` generating ray's direction
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        0.0
+    }
+    /// Random direction from origin to a point on this object at the time,
+    /// None if it can't be sampled as a light
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        None
+    }
+    /// Surface area, zero if points on this object can't be sampled
+    fn area(&self) -> f64 {
+        0.0
+    }
+    /// Random point uniformly distributed on the surface at the time, normal of the record
+    /// points outward. None if it can't be sampled by area
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        None
+    }
+    /// Fraction of light passing through this object along the ray within the limit,
+    /// may be a random estimate. Default is 0 if the ray hits anything, otherwise 1
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        if self.hit(ray, unit_limit).is_some() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
+        self.as_ref().hit(ray, unit_limit)
+    }
+
+    fn bbox(&self, time_limit: Range<f64>) -> Option<AABB> {
+        self.as_ref().bbox(time_limit)
+    }
+
+    fn is_light(&self) -> bool {
+        self.as_ref().is_light()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        self.as_ref().pdf_value(ray)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        self.as_ref().random_direction(origin, time)
+    }
+
+    fn area(&self) -> f64 {
+        self.as_ref().area()
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        self.as_ref().random_point(time)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        self.as_ref().transmittance(ray, unit_limit)
+    }
 }
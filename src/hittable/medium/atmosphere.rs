@@ -0,0 +1,119 @@
+use {
+    crate::{
+        hittable::{medium::scatter_record, HitRecord, Hittable},
+        material::{IsotropicPhase, PhaseFunction, Volume},
+        prelude::*,
+    },
+    std::ops::Range,
+};
+
+/// Participating medium filling the whole scene, attached to a `World` instead of being
+/// bounded by an object, so camera rays and rays escaping to background are affected too.
+///
+/// Density is constant, or decreases exponentially with height like real fog and air.
+#[derive(Debug)]
+pub struct Atmosphere<P = IsotropicPhase> {
+    density: f64,
+    // Height where density is the given one, and height over which density falls by 1/e
+    falloff: Option<(f64, f64)>,
+    material: Volume<P>,
+}
+
+impl Atmosphere {
+    /// Fog with the same density everywhere
+    #[must_use]
+    pub fn homogeneous(color: Color, density: f64) -> Self {
+        debug_assert!(density >= 0.0, "density = {}", density);
+        Self {
+            density,
+            falloff: None,
+            material: Volume::new(color, IsotropicPhase),
+        }
+    }
+
+    /// Fog which has the density at base height, falls by 1/e every scale height above it
+    #[must_use]
+    pub fn height_fog(color: Color, density: f64, base_height: f64, scale_height: f64) -> Self {
+        debug_assert!(density >= 0.0, "density = {}", density);
+        debug_assert!(scale_height > 0.0, "scale_height = {}", scale_height);
+        Self {
+            density,
+            falloff: Some((base_height, scale_height)),
+            material: Volume::new(color, IsotropicPhase),
+        }
+    }
+}
+
+impl<P> Atmosphere<P> {
+    /// Scatter by the phase function, default is isotropic
+    #[must_use]
+    pub fn phase<Q: PhaseFunction>(self, phase: Q) -> Atmosphere<Q> {
+        Atmosphere {
+            density: self.density,
+            falloff: self.falloff,
+            material: self.material.phase(phase),
+        }
+    }
+
+    // Density at the start of the ray, and its change rate per unit along the ray,
+    // so density is start * e^(-rate * unit) after start
+    fn density_along(&self, ray: &Ray, start: f64) -> (f64, f64) {
+        let length = ray.direction.length();
+        match self.falloff {
+            None => (self.density * length, 0.0),
+            Some((base_height, scale_height)) => {
+                let height = ray.position_after(start).y;
+                let density = self.density * (-(height - base_height) / scale_height).exp();
+                (density * length, ray.direction.y / scale_height)
+            }
+        }
+    }
+
+    fn optical_depth(&self, ray: &Ray, range: &Range<f64>) -> f64 {
+        let (density, rate) = self.density_along(ray, range.start);
+        let units = range.end - range.start;
+        if density <= 0.0 || units <= 0.0 {
+            0.0
+        } else if rate.abs() < 1e-9 {
+            density * units
+        } else {
+            -density * (-rate * units).exp_m1() / rate
+        }
+    }
+}
+
+impl<P: PhaseFunction> Hittable for Atmosphere<P> {
+    fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
+        let start = unit_limit.start.max(0.0);
+        let (density, rate) = self.density_along(ray, start);
+        if density <= 0.0 {
+            return None;
+        }
+        let depth = -(1.0 - Random::normal()).ln();
+        let units = if rate.abs() < 1e-9 {
+            depth / density
+        } else {
+            let ratio = depth * rate / density;
+            // Going up, optical depth to the infinity is limited
+            if ratio >= 1.0 {
+                return None;
+            }
+            -(-ratio).ln_1p() / rate
+        };
+        let unit = start + units;
+        if unit < unit_limit.end {
+            Some(scatter_record(ray, unit, &self.material))
+        } else {
+            None
+        }
+    }
+
+    fn bbox(&self, _time_limit: Range<f64>) -> Option<AABB> {
+        None
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        let range = unit_limit.start.max(0.0)..unit_limit.end;
+        (-self.optical_depth(ray, &range)).exp()
+    }
+}
@@ -1,13 +1,16 @@
 use crate::{
-    hittable::{HitRecord, Hittable},
-    material::Isotropic,
+    hittable::{
+        medium::{intervals, scatter_record, EmissionField},
+        HitRecord, Hittable,
+    },
+    material::{IsotropicPhase, PhaseFunction, Volume},
     prelude::*,
 };
 
 #[derive(Debug)]
-pub struct ConstantMedium<T> {
+pub struct ConstantMedium<T, P = IsotropicPhase> {
     boundary: T,
-    material: Isotropic,
+    material: Volume<P>,
     density: f64,
     neg_inv_density: f64,
 }
@@ -17,54 +20,58 @@ impl<T> ConstantMedium<T> {
     pub fn new(boundary: T, color: Color, density: f64) -> Self {
         Self {
             boundary,
-            material: Isotropic::new(color),
+            material: Volume::new(color, IsotropicPhase),
             density,
             neg_inv_density: -1.0 / density,
         }
     }
 }
 
-impl<T: Hittable> Hittable for ConstantMedium<T> {
-    fn hit(
-        &self, ray: &Ray, unit_limit: std::ops::Range<f64>,
-    ) -> Option<crate::hittable::HitRecord<'_>> {
-        let mut rec1 = self.boundary.hit(ray, f64::NEG_INFINITY..f64::INFINITY)?;
-        let mut rec2 = self.boundary.hit(ray, rec1.unit + 0.0001..f64::INFINITY)?;
-        if rec1.unit < unit_limit.start {
-            rec1.unit = unit_limit.start;
-        }
-        if rec2.unit > unit_limit.end {
-            rec2.unit = unit_limit.end;
-        }
-        if rec1.unit >= rec2.unit {
-            return None;
-        }
-        if rec1.unit < 0.0 {
-            rec1.unit = 0.0;
+impl<T, P> ConstantMedium<T, P> {
+    /// Emit light inside the medium, like fire or glowing gas
+    #[must_use]
+    pub fn emission<E: EmissionField + 'static>(mut self, emission: E) -> Self {
+        self.material = self.material.emission(emission);
+        self
+    }
+
+    /// Scatter by the phase function, default is isotropic
+    #[must_use]
+    pub fn phase<Q: PhaseFunction>(self, phase: Q) -> ConstantMedium<T, Q> {
+        ConstantMedium {
+            boundary: self.boundary,
+            material: self.material.phase(phase),
+            density: self.density,
+            neg_inv_density: self.neg_inv_density,
         }
+    }
+}
 
+impl<T: Hittable, P: PhaseFunction> Hittable for ConstantMedium<T, P> {
+    fn hit(&self, ray: &Ray, unit_limit: std::ops::Range<f64>) -> Option<HitRecord<'_>> {
         let length_per_unit = ray.direction.length();
-        let distance_inside = (rec2.unit - rec1.unit) * length_per_unit;
-        let hit_distance = self.neg_inv_density * Random::normal().ln();
+        let mut hit_distance = self.neg_inv_density * Random::normal().ln();
 
-        if hit_distance > distance_inside {
-            return None;
+        for range in intervals(&self.boundary, ray, unit_limit) {
+            let distance_inside = (range.end - range.start) * length_per_unit;
+            if hit_distance <= distance_inside {
+                let hit_point_unit = range.start + hit_distance / length_per_unit;
+                return Some(scatter_record(ray, hit_point_unit, &self.material));
+            }
+            hit_distance -= distance_inside;
         }
-
-        let hit_point_unit = rec1.unit + hit_distance / length_per_unit;
-
-        Some(HitRecord {
-            point: ray.position_after(hit_point_unit),
-            normal: Vec3::new(1.0, 0.0, 0.0), // useless,
-            material: &self.material,
-            unit: hit_point_unit,
-            u: 0.0,         // useless
-            v: 0.0,         // useless
-            outside: false, // useless
-        })
+        None
     }
 
     fn bbox(&self, time_limit: std::ops::Range<f64>) -> Option<AABB> {
         self.boundary.bbox(time_limit)
     }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: std::ops::Range<f64>) -> f64 {
+        let units: f64 = intervals(&self.boundary, ray, unit_limit)
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum();
+        (-self.density * units * ray.direction.length()).exp()
+    }
 }
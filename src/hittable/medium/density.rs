@@ -0,0 +1,147 @@
+use {
+    crate::{prelude::*, texture::Texture},
+    std::fmt::{Debug, Formatter},
+};
+
+/// Density of a participating medium varying in space
+pub trait DensityField: Send + Sync {
+    /// Density at the point, must be in `0..=max_density()`
+    fn density(&self, point: &Point3) -> f64;
+    /// Upper bound of density, used as the majorant of delta tracking
+    fn max_density(&self) -> f64;
+}
+
+/// Densities stored in a 3D grid spanning the box, trilinear interpolated between cell centers,
+/// zero outside the box
+#[derive(Clone)]
+pub struct GridDensity {
+    bbox: AABB,
+    size: [usize; 3],
+    values: Vec<f64>,
+    max: f64,
+}
+
+impl Debug for GridDensity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "GridDensity {{ bbox: {:?}, size: {:?}, max: {} }}",
+            self.bbox, self.size, self.max
+        ))
+    }
+}
+
+impl GridDensity {
+    /// Values are ordered by x first, then y, then z
+    #[must_use]
+    pub fn new(bbox: AABB, size: [usize; 3], values: Vec<f64>) -> Self {
+        debug_assert!(size.iter().all(|n| *n > 0), "size = {:?}", size);
+        debug_assert!(
+            values.len() == size[0] * size[1] * size[2],
+            "values.len() = {}",
+            values.len()
+        );
+        debug_assert!(values.iter().all(|v| *v >= 0.0), "negative density");
+        let max = values.iter().copied().fold(0.0, f64::max);
+        Self {
+            bbox,
+            size,
+            values,
+            max,
+        }
+    }
+
+    /// Grid filled by a function of point, evaluated at cell centers
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // grid size is small enough
+    pub fn from_fn<F: Fn(&Point3) -> f64>(bbox: AABB, size: [usize; 3], f: F) -> Self {
+        let extent = bbox.max() - bbox.min();
+        let mut values = Vec::with_capacity(size[0] * size[1] * size[2]);
+        for z in 0..size[2] {
+            for y in 0..size[1] {
+                for x in 0..size[0] {
+                    let offset = Vec3::new(
+                        (x as f64 + 0.5) / size[0] as f64 * extent.x,
+                        (y as f64 + 0.5) / size[1] as f64 * extent.y,
+                        (z as f64 + 0.5) / size[2] as f64 * extent.z,
+                    );
+                    values.push(f(&(bbox.min() + &offset)));
+                }
+            }
+        }
+        Self::new(bbox, size, values)
+    }
+
+    fn value(&self, x: usize, y: usize, z: usize) -> f64 {
+        self.values[(z * self.size[1] + y) * self.size[0] + x]
+    }
+}
+
+impl DensityField for GridDensity {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )] // position is clamped into the grid
+    fn density(&self, point: &Point3) -> f64 {
+        let (min, max) = (self.bbox.min(), self.bbox.max());
+        if (0..3).any(|c| point[c] < min[c] || point[c] > max[c]) {
+            return 0.0;
+        }
+        let mut index = [0; 3];
+        let mut t = [0.0; 3];
+        for c in 0..3 {
+            let last = (self.size[c] - 1) as f64;
+            let position =
+                ((point[c] - min[c]) / (max[c] - min[c])).mul_add(self.size[c] as f64, -0.5);
+            let position = clamp(position, 0.0..=last);
+            index[c] = (position as usize).min(self.size[c].saturating_sub(2));
+            t[c] = position - index[c] as f64;
+        }
+        let mut density = 0.0;
+        for corner in 0..8 {
+            let mut weight = 1.0;
+            let mut at = [0; 3];
+            for c in 0..3 {
+                let high = corner >> c & 1 == 1;
+                weight *= if high { t[c] } else { 1.0 - t[c] };
+                at[c] = (index[c] + usize::from(high)).min(self.size[c] - 1);
+            }
+            density += weight * self.value(at[0], at[1], at[2]);
+        }
+        density
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max
+    }
+}
+
+/// Density from brightness of a texture(e.g. `Perlin` noise) at the point,
+/// scaled so white means the max density
+#[derive(Debug, Clone)]
+pub struct TextureDensity<T> {
+    texture: T,
+    max: f64,
+}
+
+impl<T> TextureDensity<T> {
+    #[must_use]
+    pub fn new(texture: T, max_density: f64) -> Self {
+        debug_assert!(max_density >= 0.0, "max_density = {}", max_density);
+        Self {
+            texture,
+            max: max_density,
+        }
+    }
+}
+
+impl<T: Texture> DensityField for TextureDensity<T> {
+    fn density(&self, point: &Point3) -> f64 {
+        let color = Vec3::from(self.texture.color(0.0, 0.0, point));
+        clamp((color.x + color.y + color.z) / 3.0, 0.0..=1.0) * self.max
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max
+    }
+}
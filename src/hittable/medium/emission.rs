@@ -0,0 +1,103 @@
+use {
+    crate::{hittable::medium::DensityField, prelude::*, texture::Texture},
+    std::fmt::{Debug, Formatter},
+};
+
+/// Light emitted by a participating medium, in radiance per unit density.
+///
+/// So an optically thick region glows with this radiance, thinner ones glow less.
+pub trait EmissionField: Send + Sync {
+    fn emission(&self, point: &Point3) -> Vec3;
+}
+
+/// Emission colored by a texture(or a constant `Color`) and scaled by the multiplier
+#[derive(Debug, Clone)]
+pub struct TextureEmission<T> {
+    texture: T,
+    multiplier: f64,
+}
+
+impl<T> TextureEmission<T> {
+    #[must_use]
+    pub fn new(texture: T, multiplier: f64) -> Self {
+        debug_assert!(multiplier >= 0.0, "multiplier = {}", multiplier);
+        Self {
+            texture,
+            multiplier,
+        }
+    }
+}
+
+impl<T: Texture> EmissionField for TextureEmission<T> {
+    fn emission(&self, point: &Point3) -> Vec3 {
+        Vec3::from(self.texture.color(0.0, 0.0, point)) * self.multiplier
+    }
+}
+
+// Count of precomputed blackbody colors from zero to max temperature
+const BLACKBODY_STEPS: usize = 64;
+
+/// Black body emission driven by a temperature field(in kelvin), like fire.
+///
+/// Brightness grows with the 4th power of temperature, the hottest point has luminance
+/// of the multiplier
+#[derive(Clone)]
+pub struct BlackbodyEmission<F> {
+    temperature: F,
+    multiplier: f64,
+    colors: Vec<Vec3>,
+}
+
+impl<F: Debug> Debug for BlackbodyEmission<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "BlackbodyEmission {{ temperature: {:?}, multiplier: {} }}",
+            self.temperature, self.multiplier
+        ))
+    }
+}
+
+impl<F: DensityField> BlackbodyEmission<F> {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // steps is small
+    pub fn new(temperature: F, multiplier: f64) -> Self {
+        debug_assert!(multiplier >= 0.0, "multiplier = {}", multiplier);
+        let max = temperature.max_density();
+        let colors = (0..=BLACKBODY_STEPS)
+            .map(|i| {
+                let t = i as f64 / BLACKBODY_STEPS as f64;
+                if t > 0.0 {
+                    Spectrum::blackbody(t * max) * t.powi(4)
+                } else {
+                    Vec3::default()
+                }
+            })
+            .collect();
+        Self {
+            temperature,
+            multiplier,
+            colors,
+        }
+    }
+}
+
+impl<F: DensityField> EmissionField for BlackbodyEmission<F> {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )] // position is clamped into the table
+    fn emission(&self, point: &Point3) -> Vec3 {
+        let max = self.temperature.max_density();
+        if max <= 0.0 {
+            return Vec3::default();
+        }
+        let position = clamp(
+            self.temperature.density(point) / max * BLACKBODY_STEPS as f64,
+            0.0..=BLACKBODY_STEPS as f64,
+        );
+        let index = (position as usize).min(BLACKBODY_STEPS - 1);
+        let t = position - index as f64;
+        (&self.colors[index] * (1.0 - t) + &self.colors[index + 1] * t) * self.multiplier
+    }
+}
@@ -0,0 +1,97 @@
+use {
+    crate::{
+        hittable::{
+            medium::{intervals, scatter_record, DensityField, EmissionField},
+            HitRecord, Hittable,
+        },
+        material::{IsotropicPhase, PhaseFunction, Volume},
+        prelude::*,
+    },
+    std::ops::Range,
+};
+
+/// Participating medium whose density varies in space, like smoke, clouds and fog banks.
+///
+/// Scatter events are sampled by delta tracking, and transmittance is estimated by
+/// ratio tracking, both use max density of the field as majorant.
+#[derive(Debug)]
+pub struct HeterogeneousMedium<T, D, P = IsotropicPhase> {
+    boundary: T,
+    material: Volume<P>,
+    density: D,
+}
+
+impl<T, D> HeterogeneousMedium<T, D> {
+    #[must_use]
+    pub const fn new(boundary: T, color: Color, density: D) -> Self {
+        Self {
+            boundary,
+            material: Volume::new(color, IsotropicPhase),
+            density,
+        }
+    }
+}
+
+impl<T, D, P> HeterogeneousMedium<T, D, P> {
+    /// Emit light inside the medium, like fire or glowing gas
+    #[must_use]
+    pub fn emission<E: EmissionField + 'static>(mut self, emission: E) -> Self {
+        self.material = self.material.emission(emission);
+        self
+    }
+
+    /// Scatter by the phase function, default is isotropic
+    #[must_use]
+    pub fn phase<Q: PhaseFunction>(self, phase: Q) -> HeterogeneousMedium<T, D, Q> {
+        HeterogeneousMedium {
+            boundary: self.boundary,
+            material: self.material.phase(phase),
+            density: self.density,
+        }
+    }
+}
+
+impl<T, D: DensityField, P> HeterogeneousMedium<T, D, P> {
+    // Tentative collisions in the range, distributed by the majorant
+    fn collisions<'a>(&'a self, ray: &'a Ray, range: Range<f64>) -> impl Iterator<Item = f64> + 'a {
+        let majorant = self.density.max_density() * ray.direction.length();
+        let mut unit = range.start;
+        std::iter::from_fn(move || {
+            if majorant <= 0.0 {
+                return None;
+            }
+            unit -= (1.0 - Random::normal()).ln() / majorant;
+            if unit < range.end {
+                Some(unit)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<T: Hittable, D: DensityField, P: PhaseFunction> Hittable for HeterogeneousMedium<T, D, P> {
+    fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
+        let majorant = self.density.max_density();
+        intervals(&self.boundary, ray, unit_limit)
+            .into_iter()
+            .flat_map(|range| self.collisions(ray, range))
+            .find(|unit| {
+                Random::normal() * majorant < self.density.density(&ray.position_after(*unit))
+            })
+            .map(|unit| scatter_record(ray, unit, &self.material))
+    }
+
+    fn bbox(&self, time_limit: Range<f64>) -> Option<AABB> {
+        self.boundary.bbox(time_limit)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        let majorant = self.density.max_density();
+        intervals(&self.boundary, ray, unit_limit)
+            .into_iter()
+            .flat_map(|range| self.collisions(ray, range))
+            .map(|unit| 1.0 - self.density.density(&ray.position_after(unit)) / majorant)
+            .product()
+    }
+}
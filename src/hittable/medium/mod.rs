@@ -1,3 +1,96 @@
+pub(crate) mod atmosphere;
 pub(crate) mod constant;
+pub(crate) mod density;
+pub(crate) mod emission;
+pub(crate) mod heterogeneous;
 
-pub use constant::ConstantMedium;
+pub use {
+    atmosphere::Atmosphere,
+    constant::ConstantMedium,
+    density::{DensityField, GridDensity, TextureDensity},
+    emission::{BlackbodyEmission, EmissionField, TextureEmission},
+    heterogeneous::HeterogeneousMedium,
+};
+
+use {
+    crate::{
+        hittable::{HitRecord, Hittable},
+        material::Material,
+        prelude::*,
+    },
+    std::ops::Range,
+};
+
+// Stop looking for more boundary crossings after this many, in case the boundary is broken
+const MAX_CROSSINGS: usize = 256;
+
+// Parts of the ray limited by unit_limit which are inside the closed boundary, in order.
+//
+// All crossings are found, so boundaries can be non-convex or made of overlapping parts,
+// and rays starting inside are recognized by leaving the boundary before entering it.
+fn intervals<T: Hittable>(boundary: &T, ray: &Ray, unit_limit: Range<f64>) -> Vec<Range<f64>> {
+    let start = unit_limit.start.max(0.0);
+    let end = unit_limit.end;
+    if start >= end {
+        return vec![];
+    }
+
+    // Crossings until the first one after the end, which tells whether the end is inside
+    let mut crossings = vec![];
+    let mut from = start;
+    while crossings.len() < MAX_CROSSINGS {
+        match boundary.hit(ray, from..f64::INFINITY) {
+            Some(record) => {
+                crossings.push((record.unit, record.outside));
+                if record.unit >= end {
+                    break;
+                }
+                from = record.unit + 0.0001;
+            }
+            None => break,
+        }
+    }
+
+    // Leaving more times than entering means the ray starts inside
+    let mut depth = 0_i32;
+    let mut lowest = 0;
+    for (_, entering) in &crossings {
+        depth += if *entering { 1 } else { -1 };
+        lowest = lowest.min(depth);
+    }
+
+    let mut depth = -lowest;
+    let mut begin = start;
+    let mut result = vec![];
+    for (unit, entering) in crossings.into_iter().filter(|(unit, _)| *unit < end) {
+        if entering {
+            if depth == 0 {
+                begin = unit;
+            }
+            depth += 1;
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0 && unit > begin {
+                result.push(begin..unit);
+            }
+        }
+    }
+    if depth > 0 && end > begin {
+        result.push(begin..end);
+    }
+    result
+}
+
+// Record of a scatter event inside the medium
+fn scatter_record<'m>(ray: &Ray, unit: f64, material: &'m dyn Material) -> HitRecord<'m> {
+    HitRecord {
+        point: ray.position_after(unit),
+        normal: Vec3::new(1.0, 0.0, 0.0), // useless,
+        material,
+        unit,
+        u: 0.0,         // useless
+        v: 0.0,         // useless
+        outside: false, // useless
+        object: None,
+    }
+}
@@ -1,4 +1,5 @@
 pub(crate) mod hit;
+pub(crate) mod visibility;
 
 pub mod collection;
 pub mod geometry;
@@ -8,4 +9,5 @@ pub mod transform;
 pub use {
     geometry::{AARect, AARectMetrics, Carton, Sphere},
     hit::{HitRecord, Hittable},
+    visibility::Visibility,
 };
@@ -73,7 +73,7 @@ impl<Axis: RotationByAxis, T: Hittable> Hittable for AARotation<Axis, T> {
     fn hit(&self, ray: &Ray, unit_limit: std::ops::Range<f64>) -> Option<HitRecord<'_>> {
         let rotated_origin = Axis::rotate(&ray.origin, -self.radian);
         let rotated_direction = Axis::rotate(&ray.direction, -self.radian);
-        let rotated_ray = Ray::new(rotated_origin, rotated_direction, ray.departure_time);
+        let rotated_ray = ray.transformed(rotated_origin, rotated_direction);
         self.object.hit(&rotated_ray, unit_limit).map(|mut record| {
             record.point = Axis::rotate(&record.point, self.radian);
             record.normal = Axis::rotate(&record.normal, self.radian);
@@ -116,4 +116,40 @@ impl<Axis: RotationByAxis, T: Hittable> Hittable for AARotation<Axis, T> {
             })
             .clone()
     }
+
+    fn is_light(&self) -> bool {
+        self.object.is_light()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        let rotated_origin = Axis::rotate(&ray.origin, -self.radian);
+        let rotated_direction = Axis::rotate(&ray.direction, -self.radian);
+        let rotated_ray = ray.transformed(rotated_origin, rotated_direction);
+        self.object.pdf_value(&rotated_ray)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        let rotated_origin = Axis::rotate(origin, -self.radian);
+        self.object
+            .random_direction(&rotated_origin, time)
+            .map(|direction| Axis::rotate(&direction, self.radian))
+    }
+
+    fn area(&self) -> f64 {
+        self.object.area()
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        let mut record = self.object.random_point(time)?;
+        record.point = Axis::rotate(&record.point, self.radian);
+        record.normal = Axis::rotate(&record.normal, self.radian);
+        Some(record)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: std::ops::Range<f64>) -> f64 {
+        let rotated_origin = Axis::rotate(&ray.origin, -self.radian);
+        let rotated_direction = Axis::rotate(&ray.direction, -self.radian);
+        let rotated_ray = ray.transformed(rotated_origin, rotated_direction);
+        self.object.transmittance(&rotated_ray, unit_limit)
+    }
 }
@@ -17,11 +17,7 @@ impl<T> Translation<T> {
 
 impl<T: Hittable> Hittable for Translation<T> {
     fn hit(&self, ray: &Ray, unit_limit: std::ops::Range<f64>) -> Option<HitRecord<'_>> {
-        let moved_ray = Ray::new(
-            &ray.origin - &self.movement,
-            ray.direction.clone(),
-            ray.departure_time,
-        );
+        let moved_ray = ray.transformed(&ray.origin - &self.movement, ray.direction.clone());
         self.object.hit(&moved_ray, unit_limit).map(|mut record| {
             record.point += &self.movement;
             record
@@ -33,4 +29,33 @@ impl<T: Hittable> Hittable for Translation<T> {
             .bbox(time_limit)
             .map(|bbox| AABB::new(bbox.min() + &self.movement, bbox.max() + &self.movement))
     }
+
+    fn is_light(&self) -> bool {
+        self.object.is_light()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        let moved_ray = ray.transformed(&ray.origin - &self.movement, ray.direction.clone());
+        self.object.pdf_value(&moved_ray)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        self.object
+            .random_direction(&(origin - &self.movement), time)
+    }
+
+    fn area(&self) -> f64 {
+        self.object.area()
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        let mut record = self.object.random_point(time)?;
+        record.point += &self.movement;
+        Some(record)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: std::ops::Range<f64>) -> f64 {
+        let moved_ray = ray.transformed(&ray.origin - &self.movement, ray.direction.clone());
+        self.object.transmittance(&moved_ray, unit_limit)
+    }
 }
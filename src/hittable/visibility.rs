@@ -0,0 +1,115 @@
+use {
+    crate::{
+        hittable::{HitRecord, Hittable},
+        prelude::*,
+    },
+    std::ops::Range,
+};
+
+/// Object visible to only some kinds of rays, e.g. a fill light hidden from the camera and
+/// reflections, or an object which casts no shadow.
+///
+/// Lights are still found when sampled even if they are hidden from all those rays
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // each one is an independent switch
+pub struct Visibility<T> {
+    object: T,
+    camera: bool,
+    reflection: bool,
+    shadow: bool,
+    emission: bool,
+}
+
+impl<T> Visibility<T> {
+    /// Visible to all rays, until turned off by the builder methods
+    pub const fn new(object: T) -> Self {
+        Self {
+            object,
+            camera: true,
+            reflection: true,
+            shadow: true,
+            emission: true,
+        }
+    }
+
+    /// Seen by camera rays directly
+    #[must_use]
+    pub const fn camera(mut self, visible: bool) -> Self {
+        self.camera = visible;
+        self
+    }
+
+    /// Seen in reflection, refraction and by light bouncing around
+    #[must_use]
+    pub const fn reflection(mut self, visible: bool) -> Self {
+        self.reflection = visible;
+        self
+    }
+
+    /// Blocks light, so casts shadows
+    #[must_use]
+    pub const fn shadow(mut self, visible: bool) -> Self {
+        self.shadow = visible;
+        self
+    }
+
+    /// Sampled as a light if it's emissive, otherwise its emission is only found by chance
+    #[must_use]
+    pub const fn emission(mut self, visible: bool) -> Self {
+        self.emission = visible;
+        self
+    }
+}
+
+impl<T: Hittable> Visibility<T> {
+    fn visible(&self, ray: &Ray) -> bool {
+        match ray.kind {
+            RayKind::Camera => self.camera,
+            RayKind::Reflection => self.reflection,
+            RayKind::Shadow => self.shadow,
+            RayKind::Light => self.is_light(),
+        }
+    }
+}
+
+impl<T: Hittable> Hittable for Visibility<T> {
+    fn hit(&self, ray: &Ray, unit_limit: Range<f64>) -> Option<HitRecord<'_>> {
+        if self.visible(ray) {
+            self.object.hit(ray, unit_limit)
+        } else {
+            None
+        }
+    }
+
+    fn bbox(&self, time_limit: Range<f64>) -> Option<AABB> {
+        self.object.bbox(time_limit)
+    }
+
+    fn is_light(&self) -> bool {
+        self.emission && self.object.is_light()
+    }
+
+    fn pdf_value(&self, ray: &Ray) -> f64 {
+        self.object.pdf_value(ray)
+    }
+
+    fn random_direction(&self, origin: &Point3, time: f64) -> Option<Vec3> {
+        self.object.random_direction(origin, time)
+    }
+
+    fn area(&self) -> f64 {
+        self.object.area()
+    }
+
+    fn random_point(&self, time: f64) -> Option<HitRecord<'_>> {
+        self.object.random_point(time)
+    }
+
+    fn transmittance(&self, ray: &Ray, unit_limit: Range<f64>) -> f64 {
+        if self.visible(ray) {
+            self.object.transmittance(ray, unit_limit)
+        } else {
+            1.0
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    hittable::{collection::World, Hittable},
+    integrator::Integrator,
+    prelude::*,
+};
+
+/// Ambient occlusion, brightness is the fraction of hemisphere around hit point
+/// which is not blocked by objects in the given distance
+#[derive(Debug, Clone)]
+pub struct AmbientOcclusion {
+    distance: f64,
+    samples: usize,
+}
+
+impl AmbientOcclusion {
+    #[must_use]
+    pub fn new(distance: f64) -> Self {
+        debug_assert!(distance > 0.0, "distance = {}", distance);
+        Self {
+            distance,
+            samples: 1,
+        }
+    }
+
+    #[must_use]
+    pub fn samples(mut self, samples: usize) -> Self {
+        debug_assert!(samples > 0, "samples = {}", samples);
+        self.samples = samples;
+        self
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    #[allow(clippy::cast_precision_loss)] // sample count is small enough in practice
+    fn radiance(&self, ray: &Ray, world: &World, _depth: usize) -> Vec3 {
+        world.hit(ray, 0.001..f64::INFINITY).map_or_else(
+            || Vec3::new(1.0, 1.0, 1.0),
+            |hit| {
+                let visible = (0..self.samples)
+                    .filter(|_| {
+                        // cosine weighted direction
+                        let direction = &hit.normal + Vec3::random_unit();
+                        let length = direction.length();
+                        let probe = ray.next(hit.point.clone(), direction).kind(RayKind::Shadow);
+                        world.hit(&probe, 0.001..self.distance / length).is_none()
+                    })
+                    .count();
+                let value = visible as f64 / self.samples as f64;
+                Vec3::new(value, value, value)
+            },
+        )
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{
+    hittable::{collection::World, HitRecord, Hittable},
+    integrator::{emission_direction, emission_pdf, Integrator},
+    prelude::*,
+};
+
+#[derive(Debug)]
+enum VertexKind<'w> {
+    Camera,
+    // Point on a light, where light subpath starts
+    Light(HitRecord<'w>),
+    // Scattering point of either subpath
+    Surface(HitRecord<'w>),
+}
+
+#[derive(Debug)]
+struct Vertex<'w> {
+    kind: VertexKind<'w>,
+    point: Point3,
+    // Throughput from start of the subpath to this vertex
+    throughput: Vec3,
+    // Scattering here is specular, can't be connected to other vertices
+    delta: bool,
+    // Probability density by area of generating this vertex along the subpath,
+    // and in the opposite direction
+    pdf_fwd: f64,
+    pdf_rev: f64,
+}
+
+// Hit record seen from a point, normal faces that point
+fn seen_from<'w>(hit: &HitRecord<'w>, from: &Point3, primary: &Ray) -> (Ray, HitRecord<'w>) {
+    let ray = primary.next(from.clone(), &hit.point - from);
+    let mut hit = hit.clone();
+    if hit.normal.dot(&ray.direction) > 0.0 {
+        hit.normal.reverse();
+    }
+    (ray, hit)
+}
+
+impl<'w> Vertex<'w> {
+    const fn camera(point: Point3) -> Self {
+        Self {
+            kind: VertexKind::Camera,
+            point,
+            throughput: Vec3::new(1.0, 1.0, 1.0),
+            delta: false,
+            pdf_fwd: 1.0,
+            pdf_rev: 0.0,
+        }
+    }
+
+    const fn hit(&self) -> Option<&HitRecord<'w>> {
+        match &self.kind {
+            VertexKind::Camera => None,
+            VertexKind::Light(hit) | VertexKind::Surface(hit) => Some(hit),
+        }
+    }
+
+    // Convert density in solid angle at this vertex to density by area at next vertex
+    fn to_area(&self, pdf: f64, next: &Self) -> f64 {
+        let direction = &next.point - &self.point;
+        let distance_squared = direction.length_squared();
+        if distance_squared <= 0.0 {
+            return 0.0;
+        }
+        next.hit().map_or(pdf / distance_squared, |hit| {
+            pdf * hit.normal.dot(&direction).abs() / (distance_squared * distance_squared.sqrt())
+        })
+    }
+
+    // Density by area of this vertex emitting light to next vertex
+    fn emission_pdf(&self, next: &Self) -> f64 {
+        self.hit().map_or(0.0, |hit| {
+            self.to_area(
+                emission_pdf(&hit.normal, &(&next.point - &self.point)),
+                next,
+            )
+        })
+    }
+
+    // Density by area of sampling next vertex from this one, when prev is the vertex before
+    fn pdf(&self, prev: Option<&Self>, next: &Self, primary: &Ray) -> f64 {
+        match (&self.kind, prev) {
+            (VertexKind::Light(_), _) => self.emission_pdf(next),
+            (VertexKind::Surface(hit), Some(prev)) => {
+                let (ray, hit) = seen_from(hit, &prev.point, primary);
+                let pdf = hit.material.pdf(&ray, &hit, &(&next.point - &self.point));
+                self.to_area(pdf, next)
+            }
+            _ => 0.0,
+        }
+    }
+
+    // Bsdf multiplied by cos to next vertex, for light transported between prev and next
+    fn bsdf(&self, prev: &Self, next: &Self, primary: &Ray) -> Vec3 {
+        match &self.kind {
+            VertexKind::Light(hit) => {
+                let cos = hit.normal.dot(&(&next.point - &self.point).unit()).abs();
+                Vec3::new(cos, cos, cos)
+            }
+            VertexKind::Surface(hit) => {
+                let (ray, hit) = seen_from(hit, &prev.point, primary);
+                ray.spectrum(hit.material.bsdf(&ray, &hit, &(&next.point - &self.point)))
+            }
+            VertexKind::Camera => Vec3::default(),
+        }
+    }
+}
+
+/// Bidirectional path tracer, trace a subpath from camera and another from a random point on
+/// lights, then connect every pair of their vertices, weight the paths by balance heuristic.
+///
+/// Paths which only hit the camera by light subpath are not supported, so caustics seen
+/// directly by camera through specular surfaces still rely on camera subpath.
+/// Background is only reached by camera subpath.
+#[derive(Debug, Default, Clone)]
+pub struct BidirectionalPathTracer;
+
+impl BidirectionalPathTracer {
+    // Extend the subpath from its last vertex by scattering, start with the ray, its throughput
+    // and probability density in solid angle.
+    // Return the ray escaped from world and its throughput
+    fn random_walk<'w>(
+        world: &'w World, (mut ray, mut throughput, mut pdf): (Ray, Vec3, f64), depth: usize,
+        path: &mut Vec<Vertex<'w>>,
+    ) -> Option<(Ray, Vec3)> {
+        let mut bounce = 0;
+        while let Some(hit) = world.hit(&ray, 0.001..f64::INFINITY) {
+            if bounce == depth {
+                return None;
+            }
+            bounce += 1;
+            let material = hit.material;
+            let prev = path.len() - 1;
+            let mut vertex = Vertex {
+                kind: VertexKind::Surface(hit.clone()),
+                point: hit.point.clone(),
+                throughput: throughput.clone(),
+                delta: false,
+                pdf_fwd: 0.0,
+                pdf_rev: 0.0,
+            };
+            vertex.pdf_fwd = path[prev].to_area(pdf, &vertex);
+
+            let scattered = material.scatter(&ray, hit.clone());
+            let pdf_rev = match scattered
+                .as_ref()
+                .map(|scattered| (scattered, scattered.pdf))
+            {
+                Some((scattered, Some(scattered_pdf))) => {
+                    pdf = scattered_pdf;
+                    let (reversed, hit) =
+                        seen_from(&hit, &(&hit.point + &scattered.ray.direction), &ray);
+                    material.pdf(&reversed, &hit, &(&path[prev].point - &hit.point))
+                }
+                Some((_, None)) => {
+                    vertex.delta = true;
+                    pdf = 0.0;
+                    0.0
+                }
+                None => 0.0,
+            };
+            path[prev].pdf_rev = vertex.to_area(pdf_rev, &path[prev]);
+            path.push(vertex);
+
+            if let Some(scattered) = scattered {
+                throughput *= ray.spectrum(scattered.color.into());
+                ray = scattered.ray;
+            } else {
+                return None;
+            }
+            if throughput == Vec3::default() {
+                return None;
+            }
+        }
+        Some((ray, throughput))
+    }
+
+    // Sample a point on lights as start of light subpath, and the ray emitted from it
+    fn emit<'w>(world: &'w World, primary: &Ray) -> Option<(Vertex<'w>, (Ray, Vec3, f64))> {
+        let (hit, pdf_area) = world.light_point(primary.departure_time)?;
+        let emitted = primary.spectrum(hit.material.emitted(hit.u, hit.v, &hit.point)?);
+        let direction = emission_direction(&hit.normal);
+        let pdf_direction = emission_pdf(&hit.normal, &direction);
+        let ray = primary.next(hit.point.clone(), direction);
+        let throughput = &emitted * (2.0 * PI / pdf_area);
+        let vertex = Vertex {
+            kind: VertexKind::Light(hit.clone()),
+            point: hit.point,
+            throughput: emitted / pdf_area,
+            delta: false,
+            pdf_fwd: pdf_area,
+            pdf_rev: 0.0,
+        };
+        Some((vertex, (ray, throughput, pdf_direction)))
+    }
+
+    fn light_path<'w>(world: &'w World, primary: &Ray, depth: usize) -> Vec<Vertex<'w>> {
+        let mut path = vec![];
+        if let Some((vertex, start)) = Self::emit(world, primary) {
+            path.push(vertex);
+            if start.2 > 0.0 {
+                Self::random_walk(world, start, depth, &mut path);
+            }
+        }
+        path
+    }
+
+    // Contribution of connecting last vertices of light and camera subpaths, without mis weight
+    fn connect(world: &World, light: &[Vertex<'_>], camera: &[Vertex<'_>], primary: &Ray) -> Vec3 {
+        let qs = &light[light.len() - 1];
+        let pt = &camera[camera.len() - 1];
+        if qs.delta || pt.delta {
+            return Vec3::default();
+        }
+        let camera_bsdf = pt.bsdf(&camera[camera.len() - 2], qs, primary);
+        let light_bsdf = if light.len() == 1 {
+            qs.bsdf(qs, pt, primary)
+        } else {
+            qs.bsdf(&light[light.len() - 2], pt, primary)
+        };
+        if camera_bsdf == Vec3::default() || light_bsdf == Vec3::default() {
+            return Vec3::default();
+        }
+
+        let direction = &qs.point - &pt.point;
+        let distance_squared = direction.length_squared();
+        let distance = distance_squared.sqrt();
+        let shadow = primary
+            .next(pt.point.clone(), direction / distance)
+            .kind(RayKind::Shadow);
+        let transmittance = world.transmittance(&shadow, 0.001..distance - 0.001);
+        if transmittance <= 0.0 {
+            return Vec3::default();
+        }
+
+        &qs.throughput
+            * light_bsdf
+            * camera_bsdf
+            * &pt.throughput
+            * (transmittance / distance_squared)
+    }
+
+    // Whether camera subpath can reach the start of light subpath, which is done by a scattered
+    // ray from the next vertex of the path, so lights hidden from reflection rays can't be found
+    fn is_reflected_light(
+        world: &World, light: &[Vertex<'_>], pt: &Vertex<'_>, primary: &Ray,
+    ) -> bool {
+        let next = if light.len() >= 2 { &light[1] } else { pt };
+        let towards = &light[0].point - &next.point;
+        let shadow = primary
+            .next(next.point.clone(), towards)
+            .kind(RayKind::Shadow);
+        world.is_reflected_light(&shadow)
+    }
+
+    // Balance heuristic weight of the path made by connecting two subpaths,
+    // only strategies using at least two camera vertices are counted
+    fn mis_weight(
+        world: &World, light: &[Vertex<'_>], camera: &[Vertex<'_>], primary: &Ray,
+    ) -> f64 {
+        let (s, t) = (light.len(), camera.len());
+        let pt = &camera[t - 1];
+        let pt_minus = &camera[t - 2];
+        let mut camera_pdfs: Vec<_> = camera
+            .iter()
+            .map(|v| (v.pdf_fwd, v.pdf_rev, v.delta))
+            .collect();
+        let mut light_pdfs: Vec<_> = light
+            .iter()
+            .map(|v| (v.pdf_fwd, v.pdf_rev, v.delta))
+            .collect();
+
+        if s == 0 {
+            camera_pdfs[t - 1].1 = pt.hit().map_or(0.0, |hit| world.light_point_pdf(hit));
+            if camera_pdfs[t - 1].1 <= 0.0 {
+                // Emissive objects which are not sampled as lights can only be hit
+                return 1.0;
+            }
+            camera_pdfs[t - 2].1 = pt.emission_pdf(pt_minus);
+        } else {
+            let qs = &light[s - 1];
+            let qs_minus = if s >= 2 { Some(&light[s - 2]) } else { None };
+            camera_pdfs[t - 1].1 = qs.pdf(qs_minus, pt, primary);
+            camera_pdfs[t - 1].2 = false;
+            camera_pdfs[t - 2].1 = pt.pdf(Some(qs), pt_minus, primary);
+            light_pdfs[s - 1].1 = pt.pdf(Some(pt_minus), qs, primary);
+            light_pdfs[s - 1].2 = false;
+            if let Some(qs_minus) = qs_minus {
+                light_pdfs[s - 2].1 = qs.pdf(Some(pt), qs_minus, primary);
+            }
+        }
+
+        let light_reflected = s == 0 || Self::is_reflected_light(world, light, pt, primary);
+
+        let remap = |pdf: f64| if pdf > 0.0 { pdf } else { 1.0 };
+        let mut sum = 0.0;
+        let mut ratio = 1.0;
+        for i in (2..t).rev() {
+            ratio *= remap(camera_pdfs[i].1) / remap(camera_pdfs[i].0);
+            if !camera_pdfs[i].2 && !camera_pdfs[i - 1].2 {
+                sum += ratio;
+            }
+        }
+        ratio = 1.0;
+        for i in (0..s).rev() {
+            ratio *= remap(light_pdfs[i].1) / remap(light_pdfs[i].0);
+            let delta_before = i > 0 && light_pdfs[i - 1].2;
+            if !light_pdfs[i].2 && !delta_before && (i > 0 || light_reflected) {
+                sum += ratio;
+            }
+        }
+        1.0 / (1.0 + sum)
+    }
+}
+
+impl Integrator for BidirectionalPathTracer {
+    fn radiance(&self, ray: &Ray, world: &World, depth: usize) -> Vec3 {
+        let mut radiance = Vec3::default();
+
+        let mut camera = vec![Vertex::camera(ray.origin.clone())];
+        let one = Vec3::new(1.0, 1.0, 1.0);
+        if let Some((escaped, throughput)) =
+            Self::random_walk(world, (ray.clone(), one, 1.0), depth, &mut camera)
+        {
+            radiance += &throughput * ray.spectrum(world.background(&escaped).into());
+        }
+        let light = Self::light_path(world, ray, depth.saturating_sub(1));
+
+        for t in 2..=camera.len() {
+            let pt = &camera[t - 1];
+            if let Some(emitted) = pt
+                .hit()
+                .and_then(|hit| hit.material.emitted(hit.u, hit.v, &hit.point))
+            {
+                let weight = Self::mis_weight(world, &[], &camera[..t], ray);
+                radiance += &pt.throughput * ray.spectrum(emitted) * weight;
+            }
+            for s in 1..=light.len().min((depth + 1).saturating_sub(t)) {
+                let contribution = Self::connect(world, &light[..s], &camera[..t], ray);
+                if contribution != Vec3::default() {
+                    let weight = Self::mis_weight(world, &light[..s], &camera[..t], ray);
+                    radiance += contribution * weight;
+                }
+            }
+        }
+
+        radiance
+    }
+}
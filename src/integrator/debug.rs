@@ -0,0 +1,43 @@
+use crate::{
+    hittable::{collection::World, Hittable},
+    integrator::Integrator,
+    prelude::*,
+};
+
+/// Show geometry information of the first hit instead of lighting
+#[derive(Debug, Clone)]
+pub enum DebugIntegrator {
+    /// Normal facing the ray, mapped from [-1, 1] to [0, 1]
+    Normal,
+    /// Texture coordinate u in red channel and v in green channel
+    UV,
+    /// Surface color, or emitted color for lights
+    Albedo,
+    /// Distance to hit point, from white at 0 to black at the given max distance
+    Depth(f64),
+}
+
+impl Integrator for DebugIntegrator {
+    fn radiance(&self, ray: &Ray, world: &World, _depth: usize) -> Vec3 {
+        world
+            .hit(ray, 0.001..f64::INFINITY)
+            .map_or_else(Vec3::default, |hit| match self {
+                Self::Normal => (&hit.normal + Vec3::new(1.0, 1.0, 1.0)) * 0.5,
+                Self::UV => Vec3::new(hit.u, hit.v, 0.0),
+                Self::Albedo => {
+                    let material = hit.material;
+                    let emitted = material.emitted(hit.u, hit.v, &hit.point);
+                    material
+                        .scatter(ray, hit)
+                        .map(|scattered| scattered.color.into())
+                        .or(emitted)
+                        .unwrap_or_default()
+                }
+                Self::Depth(max_distance) => {
+                    let distance = hit.unit * ray.direction.length();
+                    let value = 1.0 - clamp(distance / max_distance, 0.0..=1.0);
+                    Vec3::new(value, value, value)
+                }
+            })
+    }
+}
@@ -0,0 +1,118 @@
+use {
+    crate::{
+        hittable::{collection::World, HitRecord, Hittable},
+        material::{reflect, Matte},
+        prelude::*,
+    },
+    std::fmt::Debug,
+};
+
+pub(crate) mod ao;
+pub(crate) mod bdpt;
+pub(crate) mod debug;
+pub(crate) mod path;
+pub(crate) mod photon;
+pub(crate) mod whitted;
+
+pub use {
+    ao::AmbientOcclusion,
+    bdpt::BidirectionalPathTracer,
+    debug::DebugIntegrator,
+    path::{MisStrategy, PathTracer},
+    photon::PhotonMapper,
+    whitted::{PointLight, Whitted},
+};
+
+// Random direction emitted from a point on lights, both sides of lights are emissive
+fn emission_direction(normal: &Vec3) -> Vec3 {
+    let side = if Random::normal() < 0.5 {
+        normal.clone()
+    } else {
+        -normal
+    };
+    side + Vec3::random_unit()
+}
+
+// Density in solid angle of `emission_direction` generating the direction
+fn emission_pdf(normal: &Vec3, direction: &Vec3) -> f64 {
+    normal.dot(&direction.unit()).abs() / (2.0 * PI)
+}
+
+// Times to retry when sampled light is below the surface
+const SHADOW_TRIES: usize = 8;
+
+// Whether light is blocked at the point, estimated by one light direction above the surface,
+// so averaged over samples it's the fraction of blocked light. Without sampled lights, the point
+// is lit by background only, so its occlusion by objects is the shadow
+fn shadow(ray: &Ray, hit: &HitRecord<'_>, world: &World) -> f64 {
+    if !world.has_lights() {
+        let direction = &hit.normal + Vec3::random_unit();
+        let probe = ray.next(hit.point.clone(), direction).kind(RayKind::Shadow);
+        return if world.hit_object(&probe).is_some() {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    for _ in 0..SHADOW_TRIES {
+        let Some(direction) = world.light_direction(&hit.point, ray.departure_time) else {
+            return 0.0;
+        };
+        if hit.normal.dot(&direction) > 0.0 {
+            let shadow = ray.next(hit.point.clone(), direction).kind(RayKind::Shadow);
+            return 1.0 - world.light_transmittance(&shadow);
+        }
+    }
+    0.0
+}
+
+/// Light transport algorithm, calculate radiance arriving camera along a ray
+pub trait Integrator: Debug + Send + Sync {
+    /// Build data depending on the world before rendering it at the depth, like photon maps.
+    /// Default does nothing
+    #[allow(unused_variables)]
+    fn prepare(&self, world: &World, depth: usize) {}
+
+    fn radiance(&self, ray: &Ray, world: &World, depth: usize) -> Vec3;
+
+    /// Radiance along the ray whose first hit in the world, by `world.hit`, is already found.
+    /// Default traces the ray again, integrators can override it to continue from the hit
+    #[allow(unused_variables)]
+    fn radiance_from<'w>(
+        &self, ray: &Ray, hit: Option<HitRecord<'w>>, world: &'w World, depth: usize,
+    ) -> Vec3 {
+        self.radiance(ray, world, depth)
+    }
+
+    /// Radiance and alpha(coverage) of a camera ray, for output with alpha channel.
+    /// Background seen directly is transparent, and not included in the radiance, scattering
+    /// in media like atmosphere covers the pixel.
+    ///
+    /// Holdouts are transparent. Shadow catchers have reflections of objects as radiance, and
+    /// alpha is the shadow or brightness of the reflection, whichever covers more
+    fn radiance_alpha(&self, ray: &Ray, world: &World, depth: usize) -> (Vec3, f64) {
+        let Some(hit) = world.hit(ray, 0.001..f64::INFINITY) else {
+            return (Vec3::default(), 0.0);
+        };
+        match hit.material.matte() {
+            None => (self.radiance_from(ray, Some(hit), world, depth), 1.0),
+            Some(Matte::Holdout) => (Vec3::default(), 0.0),
+            Some(Matte::ShadowCatcher(reflection)) => {
+                let mut radiance = Vec3::default();
+                if reflection > 0.0 && depth > 1 {
+                    let reflected = reflect(ray, &hit);
+                    if let Some(reflected_hit) = world
+                        .hit(&reflected, 0.001..f64::INFINITY)
+                        .filter(|record| record.material.matte().is_none())
+                    {
+                        radiance =
+                            self.radiance_from(&reflected, Some(reflected_hit), world, depth - 1)
+                                * reflection;
+                    }
+                }
+                let coverage = Color::from(radiance.clone()).luminance().min(1.0);
+                (radiance, shadow(ray, &hit, world).max(coverage))
+            }
+        }
+    }
+}
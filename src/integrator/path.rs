@@ -0,0 +1,245 @@
+use crate::{
+    hittable::{collection::World, HitRecord, Hittable},
+    integrator::Integrator,
+    prelude::*,
+};
+
+/// How to combine light sampling and bsdf sampling at non-specular hit points
+#[derive(Debug, Clone, PartialEq)]
+pub enum MisStrategy {
+    /// Take both samples, weight them by balance heuristic
+    Balance,
+    /// Take both samples, weight them by power heuristic with exponent 2
+    Power,
+    /// Take one sample from the mixture of light and bsdf distribution,
+    /// the value is probability of sampling lights
+    Mixture(f64),
+}
+
+impl MisStrategy {
+    fn weight(&self, pdf: f64, other: f64) -> f64 {
+        match self {
+            Self::Balance => pdf / (pdf + other),
+            Self::Power => pdf * pdf / pdf.mul_add(pdf, other * other),
+            Self::Mixture(_) => 1.0,
+        }
+    }
+}
+
+// How to count emission of sampled lights hit by current ray
+#[derive(Debug, Clone)]
+enum EmissionWeight {
+    // Light sampling is not involved, e.g. camera ray or specular scatter
+    Full,
+    // Already counted by light sampling at last hit point
+    Zero,
+    // Generated by bsdf sampling with the pdf, weight it against light sampling
+    Mis(f64),
+}
+
+/// Path tracer, follow the scattered ray until it escapes, is absorbed or reaches max depth.
+///
+/// If russian roulette is enabled, path is terminated randomly according to its throughput
+/// after the minimum depth, and max depth is ignored.
+///
+/// If light sampling is enabled, every non-specular hit point also connects to a random point
+/// on lights by a shadow ray, optionally combined with bsdf sampling by multiple importance
+/// sampling.
+#[derive(Debug, Default, Clone)]
+pub struct PathTracer {
+    russian_roulette: Option<usize>,
+    light_sampling: bool,
+    mis: Option<MisStrategy>,
+}
+
+impl PathTracer {
+    #[must_use]
+    pub const fn russian_roulette(mut self, min_depth: usize) -> Self {
+        self.russian_roulette = Some(min_depth);
+        self
+    }
+
+    #[must_use]
+    pub const fn light_sampling(mut self, light_sampling: bool) -> Self {
+        self.light_sampling = light_sampling;
+        self
+    }
+
+    /// Enable light sampling and combine it with bsdf sampling
+    #[must_use]
+    pub fn mis(mut self, strategy: MisStrategy) -> Self {
+        if let MisStrategy::Mixture(p) = strategy {
+            debug_assert!((0.0..=1.0).contains(&p), "p = {}", p);
+        }
+        self.light_sampling = true;
+        self.mis = Some(strategy);
+        self
+    }
+
+    fn emitted(
+        &self, ray: &Ray, hit: &HitRecord<'_>, world: &World, weight: &EmissionWeight,
+    ) -> Vec3 {
+        let emitted = hit
+            .material
+            .emitted(hit.u, hit.v, &hit.point)
+            .map(|emitted| ray.spectrum(emitted));
+        if !world.is_sampled_light(hit) {
+            return emitted.unwrap_or_default();
+        }
+        match (emitted, weight, &self.mis) {
+            (Some(emitted), EmissionWeight::Full, _) => emitted,
+            (Some(emitted), EmissionWeight::Mis(pdf), Some(mis)) => {
+                emitted * mis.weight(*pdf, world.light_pdf(ray))
+            }
+            _ => Vec3::default(),
+        }
+    }
+
+    pub(super) fn direct_light(&self, ray: &Ray, hit: &HitRecord<'_>, world: &World) -> Vec3 {
+        let Some(direction) = world.light_direction(&hit.point, ray.departure_time) else {
+            return Vec3::default();
+        };
+        let shadow = ray.next(hit.point.clone(), direction).kind(RayKind::Shadow);
+        let pdf = world.light_pdf(&shadow);
+        if pdf <= 0.0 {
+            return Vec3::default();
+        }
+        let bsdf = ray.spectrum(hit.material.bsdf(ray, hit, &shadow.direction));
+        if bsdf == Vec3::default() {
+            return Vec3::default();
+        }
+        let weight = self.mis.as_ref().map_or(1.0, |mis| {
+            // Bsdf sampling can't find lights hidden from reflection rays
+            let bsdf_pdf = if world.is_reflected_light(&shadow) {
+                hit.material.pdf(ray, hit, &shadow.direction)
+            } else {
+                0.0
+            };
+            mis.weight(pdf, bsdf_pdf)
+        });
+        world
+            .light_radiance(&shadow)
+            .map_or_else(Vec3::default, |emitted| {
+                bsdf * ray.spectrum(emitted) * (weight / pdf)
+            })
+    }
+
+    fn escaped(&self, ray: &Ray, world: &World, weight: &EmissionWeight) -> Vec3 {
+        let background = ray.spectrum(world.background(ray).into());
+        if !world.is_sampled_environment() {
+            return background;
+        }
+        match (weight, &self.mis) {
+            (EmissionWeight::Full, _) => background,
+            (EmissionWeight::Mis(pdf), Some(mis)) => {
+                background * mis.weight(*pdf, world.light_pdf(ray))
+            }
+            _ => Vec3::default(),
+        }
+    }
+
+    // One sample mis, return next ray and its weight
+    fn mixture_scatter(
+        ray: &Ray, hit: &HitRecord<'_>, world: &World, light_probability: f64,
+    ) -> Option<(Ray, Vec3)> {
+        let material = hit.material;
+        let direction = if Random::normal() < light_probability {
+            world.light_direction(&hit.point, ray.departure_time)?
+        } else {
+            material.scatter(ray, hit.clone())?.ray.direction
+        };
+        let next = ray.next(hit.point.clone(), direction);
+        let pdf = light_probability.mul_add(
+            world.light_pdf(&next),
+            (1.0 - light_probability) * material.pdf(ray, hit, &next.direction),
+        );
+        if pdf <= 0.0 {
+            return None;
+        }
+        let weight = ray.spectrum(material.bsdf(ray, hit, &next.direction)) / pdf;
+        Some((next, weight))
+    }
+
+    // Probability to continue the path, None means path should be terminated
+    fn survive(&self, bounce: usize, depth: usize, throughput: &Vec3) -> Option<f64> {
+        match self.russian_roulette {
+            Some(min_depth) if bounce >= min_depth => {
+                let p = throughput.x.max(throughput.y).max(throughput.z).min(0.95);
+                if Random::normal() < p {
+                    Some(p)
+                } else {
+                    None
+                }
+            }
+            Some(_) => Some(1.0),
+            None if bounce < depth => Some(1.0),
+            None => None,
+        }
+    }
+}
+
+impl Integrator for PathTracer {
+    fn radiance(&self, ray: &Ray, world: &World, depth: usize) -> Vec3 {
+        self.radiance_from(ray, world.hit(ray, 0.001..f64::INFINITY), world, depth)
+    }
+
+    fn radiance_from<'w>(
+        &self, ray: &Ray, hit: Option<HitRecord<'w>>, world: &'w World, depth: usize,
+    ) -> Vec3 {
+        let mut radiance = Vec3::default();
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+        let mut ray = ray.clone();
+        let mut bounce = 0;
+        let mut emission_weight = EmissionWeight::Full;
+        let mut first = Some(hit);
+        let trace = |ray: &Ray| world.hit(ray, 0.001..f64::INFINITY);
+
+        while let Some(p) = self.survive(bounce, depth, &throughput) {
+            throughput /= p;
+
+            if let Some(hit) = first.take().unwrap_or_else(|| trace(&ray)) {
+                radiance += &throughput * self.emitted(&ray, &hit, world, &emission_weight);
+
+                if !self.light_sampling || !world.has_lights() || hit.material.delta() {
+                    if let Some(scattered) = hit.material.scatter(&ray, hit) {
+                        throughput *= ray.spectrum(scattered.color.into());
+                        ray = scattered.ray;
+                        emission_weight = EmissionWeight::Full;
+                    } else {
+                        break;
+                    }
+                } else if let Some(MisStrategy::Mixture(light_probability)) = self.mis {
+                    if let Some((next, weight)) =
+                        Self::mixture_scatter(&ray, &hit, world, light_probability)
+                    {
+                        throughput *= weight;
+                        ray = next;
+                        emission_weight = EmissionWeight::Full;
+                    } else {
+                        break;
+                    }
+                } else {
+                    radiance += &throughput * self.direct_light(&ray, &hit, world);
+                    if let Some(scattered) = hit.material.scatter(&ray, hit) {
+                        throughput *= ray.spectrum(scattered.color.into());
+                        ray = scattered.ray;
+                        emission_weight = match (&self.mis, scattered.pdf) {
+                            (None, _) => EmissionWeight::Zero,
+                            (Some(_), Some(pdf)) => EmissionWeight::Mis(pdf),
+                            (Some(_), None) => EmissionWeight::Full,
+                        };
+                    } else {
+                        break;
+                    }
+                }
+            } else {
+                radiance += &throughput * self.escaped(&ray, world, &emission_weight);
+                break;
+            }
+
+            bounce += 1;
+        }
+
+        radiance
+    }
+}
@@ -0,0 +1,288 @@
+use {
+    crate::{
+        hittable::{collection::World, HitRecord, Hittable},
+        integrator::{emission_direction, emission_pdf, Integrator, PathTracer},
+        prelude::*,
+    },
+    rayon::prelude::*,
+    std::{
+        fmt::{Debug, Formatter},
+        sync::{Arc, RwLock},
+    },
+};
+
+#[derive(Debug, Clone)]
+struct Photon {
+    point: Point3,
+    // Normal of the surface, facing where the photon comes from
+    normal: Vec3,
+    // Travelling direction when arriving
+    direction: Vec3,
+    power: Vec3,
+}
+
+// Balanced kd-tree stored in an array, the node of a range is its middle element,
+// split axis of the node is stored at the same index of axes
+#[derive(Clone)]
+struct PhotonMap {
+    photons: Vec<Photon>,
+    axes: Vec<usize>,
+}
+
+impl Debug for PhotonMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "PhotonMap {{ photons: {} }}",
+            self.photons.len()
+        ))
+    }
+}
+
+impl PhotonMap {
+    fn new(mut photons: Vec<Photon>) -> Self {
+        let mut axes = vec![0; photons.len()];
+        Self::build(&mut photons, &mut axes);
+        Self { photons, axes }
+    }
+
+    fn build(photons: &mut [Photon], axes: &mut [usize]) {
+        if photons.len() <= 1 {
+            return;
+        }
+        let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for photon in photons.iter() {
+            for c in 0..3 {
+                min[c] = min[c].min(photon.point[c]);
+                max[c] = max[c].max(photon.point[c]);
+            }
+        }
+        let extent = max - min;
+        let split = (0..3)
+            .max_by(|a, b| extent[*a].total_cmp(&extent[*b]))
+            .unwrap_or_default();
+
+        let mid = photons.len() / 2;
+        photons.select_nth_unstable_by(mid, |a, b| a.point[split].total_cmp(&b.point[split]));
+        axes[mid] = split;
+        let (left, right) = photons.split_at_mut(mid);
+        let (left_axes, right_axes) = axes.split_at_mut(mid);
+        rayon::join(
+            || Self::build(left, left_axes),
+            || Self::build(&mut right[1..], &mut right_axes[1..]),
+        );
+    }
+
+    // Call f with every photon within radius of the point
+    fn search<F: FnMut(&Photon)>(&self, point: &Point3, radius: f64, f: &mut F) {
+        Self::search_in(&self.photons, &self.axes, point, radius, f);
+    }
+
+    fn search_in<F: FnMut(&Photon)>(
+        photons: &[Photon], axes: &[usize], point: &Point3, radius: f64, f: &mut F,
+    ) {
+        if photons.is_empty() {
+            return;
+        }
+        let mid = photons.len() / 2;
+        let photon = &photons[mid];
+        if (&photon.point - point).length_squared() <= radius * radius {
+            f(photon);
+        }
+        let offset = point[axes[mid]] - photon.point[axes[mid]];
+        if offset <= radius {
+            Self::search_in(&photons[..mid], &axes[..mid], point, radius, f);
+        }
+        if offset >= -radius {
+            Self::search_in(&photons[mid + 1..], &axes[mid + 1..], point, radius, f);
+        }
+    }
+}
+
+/// Path tracer with a caustic photon map.
+///
+/// Photons are emitted from lights, and stored where they first hit a non-specular surface
+/// after at least one specular bounce, so caustics from `Dielectric` and mirror `Metal` are
+/// estimated by photon density. Camera paths sample lights directly and gather photons at
+/// non-specular surfaces, emission reached after that is ignored because it's already counted.
+///
+/// The photon map is built by `prepare` for a world and depth, rendering other worlds gets no
+/// caustics. Only lights sampled by area emit photons, environment lighting doesn't, and
+/// photons carry rgb power, so in spectral rendering caustics don't show dispersion.
+#[derive(Debug)]
+pub struct PhotonMapper {
+    photons: usize,
+    radius: Option<f64>,
+    path_tracer: PathTracer,
+    map: RwLock<Option<Arc<PreparedMap>>>,
+}
+
+// Photon map with gathering radius, and which world and depth it's built for
+#[derive(Debug)]
+struct PreparedMap {
+    map: PhotonMap,
+    radius: f64,
+    // Id of the world
+    world: usize,
+    depth: usize,
+}
+
+impl PreparedMap {
+    const fn is_for(&self, world: &World, depth: usize) -> bool {
+        self.world == world.id() && self.depth == depth
+    }
+}
+
+impl Default for PhotonMapper {
+    fn default() -> Self {
+        Self {
+            photons: 200_000,
+            radius: None,
+            path_tracer: PathTracer::default().light_sampling(true),
+            map: RwLock::new(None),
+        }
+    }
+}
+
+impl PhotonMapper {
+    /// Count of photons emitted from lights
+    #[must_use]
+    pub fn photons(mut self, count: usize) -> Self {
+        debug_assert!(count > 0, "count = {}", count);
+        self.photons = count;
+        self
+    }
+
+    /// Radius of gathering photons, default is 1/200 of the diagonal of world's bounding box
+    #[must_use]
+    pub fn radius(mut self, radius: f64) -> Self {
+        debug_assert!(radius > 0.0, "radius = {}", radius);
+        self.radius = Some(radius);
+        self
+    }
+
+    // Photon stored by one emitted from lights, None if it's absorbed or doesn't make caustics
+    fn trace_photon(world: &World, depth: usize, scale: f64) -> Option<Photon> {
+        let (hit, pdf_area) = world.light_point(0.0)?;
+        let emitted = hit.material.emitted(hit.u, hit.v, &hit.point)?;
+        let direction = emission_direction(&hit.normal);
+        if emission_pdf(&hit.normal, &direction) <= 0.0 {
+            return None;
+        }
+        let mut power = emitted * (2.0 * PI / pdf_area * scale);
+        let mut ray = Ray::new(hit.point, direction, 0.0).kind(RayKind::Reflection);
+        let mut specular = false;
+        for _ in 0..depth {
+            let hit = world.hit(&ray, 0.001..f64::INFINITY)?;
+            let material = hit.material;
+            if !material.delta() {
+                return if specular {
+                    Some(Photon {
+                        point: hit.point,
+                        normal: hit.normal,
+                        direction: ray.direction.unit(),
+                        power,
+                    })
+                } else {
+                    None
+                };
+            }
+            specular = true;
+            let scattered = material.scatter(&ray, hit)?;
+            power = power * scattered.color;
+            ray = scattered.ray;
+        }
+        None
+    }
+
+    fn caustics(map: &PhotonMap, radius: f64, ray: &Ray, hit: &HitRecord<'_>) -> Vec3 {
+        let mut sum = Vec3::default();
+        map.search(&hit.point, radius, &mut |photon| {
+            // Skip photons on other surfaces, like the other side of a thin wall
+            if photon.normal.dot(&hit.normal) < 0.9 {
+                return;
+            }
+            let incoming = -&photon.direction;
+            let cos = hit.normal.dot(&incoming);
+            if cos > 0.0 {
+                sum += hit.material.bsdf(ray, hit, &incoming) * &photon.power / cos;
+            }
+        });
+        ray.spectrum(sum / (PI * radius * radius))
+    }
+}
+
+impl Integrator for PhotonMapper {
+    #[allow(clippy::cast_precision_loss)] // photon count is small enough
+    fn prepare(&self, world: &World, depth: usize) {
+        let scale = 1.0 / self.photons as f64;
+        let photons = (0..self.photons)
+            .into_par_iter()
+            .filter_map(|_| Self::trace_photon(world, depth, scale))
+            .collect();
+        let radius = self.radius.unwrap_or_else(|| {
+            world
+                .bbox(0.0..0.0)
+                .map_or(1.0, |bbox| (bbox.max() - bbox.min()).length() / 200.0)
+        });
+        let prepared = PreparedMap {
+            map: PhotonMap::new(photons),
+            radius,
+            world: world.id(),
+            depth,
+        };
+        *self.map.write().unwrap() = Some(Arc::new(prepared));
+    }
+
+    fn radiance(&self, ray: &Ray, world: &World, depth: usize) -> Vec3 {
+        self.radiance_from(ray, world.hit(ray, 0.001..f64::INFINITY), world, depth)
+    }
+
+    fn radiance_from<'w>(
+        &self, ray: &Ray, hit: Option<HitRecord<'w>>, world: &'w World, depth: usize,
+    ) -> Vec3 {
+        let prepared = self.map.read().unwrap().clone();
+        let prepared = prepared.filter(|prepared| prepared.is_for(world, depth));
+        let mut radiance = Vec3::default();
+        let mut throughput = Vec3::new(1.0, 1.0, 1.0);
+        let mut ray = ray.clone();
+        let mut diffused = false;
+
+        let mut first = Some(hit);
+        let trace = |ray: &Ray| world.hit(ray, 0.001..f64::INFINITY);
+
+        for _ in 0..depth {
+            if let Some(hit) = first.take().unwrap_or_else(|| trace(&ray)) {
+                let material = hit.material;
+                if !diffused || !world.is_sampled_light(&hit) {
+                    if let Some(emitted) = material.emitted(hit.u, hit.v, &hit.point) {
+                        radiance += &throughput * ray.spectrum(emitted);
+                    }
+                }
+                if !material.delta() {
+                    diffused = true;
+                    let mut direct = prepared.as_ref().map_or_else(Vec3::default, |prepared| {
+                        Self::caustics(&prepared.map, prepared.radius, &ray, &hit)
+                    });
+                    if world.has_lights() {
+                        direct += self.path_tracer.direct_light(&ray, &hit, world);
+                    }
+                    radiance += &throughput * direct;
+                }
+                if let Some(scattered) = material.scatter(&ray, hit) {
+                    throughput *= ray.spectrum(scattered.color.into());
+                    ray = scattered.ray;
+                } else {
+                    break;
+                }
+            } else {
+                if !diffused || !world.is_sampled_environment() {
+                    radiance += &throughput * ray.spectrum(world.background(&ray).into());
+                }
+                break;
+            }
+        }
+
+        radiance
+    }
+}
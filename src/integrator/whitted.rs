@@ -0,0 +1,110 @@
+use crate::{
+    hittable::{collection::World, Hittable},
+    integrator::Integrator,
+    prelude::*,
+};
+
+#[derive(Debug, Clone)]
+pub struct PointLight {
+    position: Point3,
+    color: Color,
+    multiplier: f64,
+}
+
+impl PointLight {
+    #[must_use]
+    pub const fn new(position: Point3, color: Color) -> Self {
+        Self {
+            position,
+            color,
+            multiplier: 1.0,
+        }
+    }
+
+    /// Light of blackbody color at the temperature in kelvin, whose luminous intensity is 1
+    /// before multiplied, so it can be set by `candela`
+    #[must_use]
+    pub fn temperature(position: Point3, temperature: f64) -> Self {
+        Self::new(position, Spectrum::blackbody(temperature).into())
+    }
+
+    #[must_use]
+    pub const fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set multiplier so a white light has the luminous intensity in candela
+    #[must_use]
+    pub fn candela(mut self, intensity: f64) -> Self {
+        debug_assert!(intensity >= 0.0, "intensity = {}", intensity);
+        self.multiplier = intensity / NITS_PER_UNIT;
+        self
+    }
+}
+
+/// Whitted-style ray tracer, diffuse surfaces are lit by point lights with hard shadows,
+/// specular surfaces follow the reflected or refracted ray
+#[derive(Debug, Default)]
+pub struct Whitted {
+    lights: Vec<PointLight>,
+}
+
+impl Whitted {
+    #[must_use]
+    pub fn light(mut self, light: PointLight) -> Self {
+        self.lights.push(light);
+        self
+    }
+
+    fn direct(&self, world: &World, point: &Point3, normal: &Vec3, time: f64) -> Vec3 {
+        self.lights
+            .iter()
+            .filter_map(|light| {
+                let to_light = &light.position - point;
+                let distance_squared = to_light.length_squared();
+                let cos = normal.dot(&to_light) / distance_squared.sqrt();
+                if cos <= 0.0 {
+                    return None;
+                }
+                let shadow = Ray::new(point.clone(), to_light, time).kind(RayKind::Shadow);
+                let transmittance = world.transmittance(&shadow, 0.001..1.0);
+                if transmittance <= 0.0 {
+                    return None;
+                }
+                let intensity: Vec3 = light.color.clone().into();
+                Some(intensity * (light.multiplier * transmittance * cos / distance_squared))
+            })
+            .sum()
+    }
+}
+
+impl Integrator for Whitted {
+    fn radiance(&self, ray: &Ray, world: &World, depth: usize) -> Vec3 {
+        if depth == 0 {
+            return Vec3::default();
+        }
+
+        if let Some(hit) = world.hit(ray, 0.001..f64::INFINITY) {
+            let material = hit.material;
+            let emitted = material
+                .emitted(hit.u, hit.v, &hit.point)
+                .unwrap_or_default();
+            let incoming = if material.specular() {
+                None
+            } else {
+                Some(self.direct(world, &hit.point, &hit.normal, ray.departure_time) / PI)
+            };
+            return match material.scatter(ray, hit) {
+                Some(scattered) => {
+                    let incoming =
+                        incoming.unwrap_or_else(|| self.radiance(&scattered.ray, world, depth - 1));
+                    emitted + scattered.color * incoming
+                }
+                None => emitted,
+            };
+        }
+
+        world.background(ray).into()
+    }
+}
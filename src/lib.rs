@@ -4,7 +4,9 @@
 #![allow(clippy::module_name_repetitions, clippy::cast_possible_truncation)]
 
 pub mod camera;
+pub mod environment;
 pub mod hittable;
+pub mod integrator;
 pub mod material;
 pub mod painter;
 pub mod prelude;
@@ -22,23 +22,59 @@ impl ReflectProbabilityCurve for Glass {
     }
 }
 
+/// Refractive index varying with wavelength(in micrometers in the formulas)
+#[derive(Debug, Clone)]
+pub enum Dispersion {
+    /// n = a + b / wavelength^2
+    Cauchy { a: f64, b: f64 },
+    /// n^2 = 1 + sum(b * wavelength^2 / (wavelength^2 - c))
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Borosilicate crown glass, the most common optical glass
+    #[must_use]
+    pub const fn bk7() -> Self {
+        Self::Sellmeier {
+            b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        }
+    }
+
+    /// Refractive index at the wavelength in nanometers
+    #[must_use]
+    pub fn refractive(&self, wavelength: f64) -> f64 {
+        let micrometers = wavelength / 1000.0;
+        let squared = micrometers * micrometers;
+        match self {
+            Self::Cauchy { a, b } => a + b / squared,
+            Self::Sellmeier { b, c } => {
+                let sum: f64 = b
+                    .iter()
+                    .zip(c.iter())
+                    .map(|(b, c)| b * squared / (squared - c))
+                    .sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Dielectric {
     color: Color,
-    enter_refractive: f64,
-    outer_refractive: f64,
+    refractive: f64,
+    dispersion: Option<Dispersion>,
     reflect_curve: Option<Box<dyn ReflectProbabilityCurve>>,
 }
 
 impl Dielectric {
     #[must_use]
-    pub fn new(color: Color, refractive: f64) -> Self {
-        let enter_refractive = 1.0 / refractive;
-        let outer_refractive = refractive;
+    pub const fn new(color: Color, refractive: f64) -> Self {
         Self {
             color,
-            enter_refractive,
-            outer_refractive,
+            refractive,
+            dispersion: None,
             reflect_curve: None,
         }
     }
@@ -48,14 +84,25 @@ impl Dielectric {
         self
     }
 
+    /// Refractive index for rays with wavelength, others still use the constant one
+    #[must_use]
+    pub const fn dispersion(mut self, dispersion: Dispersion) -> Self {
+        self.dispersion = Some(dispersion);
+        self
+    }
+
     fn refract(&self, ray: &Ray, hit: &HitRecord<'_>) -> Option<Ray> {
         let dir = ray.direction.unit();
         let cos_theta = (-&dir).dot(&hit.normal);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let refractive = match (&self.dispersion, ray.wavelength) {
+            (Some(dispersion), Some(wavelength)) => dispersion.refractive(wavelength),
+            _ => self.refractive,
+        };
         let refractive = if hit.outside {
-            self.enter_refractive
+            1.0 / refractive
         } else {
-            self.outer_refractive
+            refractive
         };
         if refractive * sin_theta > 1.0 {
             return None;
@@ -70,7 +117,7 @@ impl Dielectric {
         let r_parallel = refractive * (&dir + cos_theta * &hit.normal);
         let r_perpendicular = -(1.0 - r_parallel.length_squared()).sqrt() * &hit.normal;
         let r = r_parallel + r_perpendicular;
-        Some(Ray::new(hit.point.clone(), r, ray.departure_time))
+        Some(ray.next(hit.point.clone(), r))
     }
 }
 
@@ -82,6 +129,11 @@ impl Material for Dielectric {
         Some(ScatterRecord {
             color: self.color.clone(),
             ray: refract,
+            pdf: None,
         })
     }
+
+    fn specular(&self) -> bool {
+        true
+    }
 }
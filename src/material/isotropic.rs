@@ -1,4 +1,5 @@
 use crate::{
+    hittable::HitRecord,
     material::{Material, ScatterRecord},
     prelude::*,
 };
@@ -16,13 +17,20 @@ impl Isotropic {
 }
 
 impl Material for Isotropic {
-    fn scatter(
-        &self, ray: &Ray, hit: crate::hittable::HitRecord<'_>,
-    ) -> Option<super::ScatterRecord> {
-        let scattered_ray = Ray::new(hit.point, Vec3::random_in_unit_sphere(), ray.departure_time);
+    fn scatter(&self, ray: &Ray, hit: HitRecord<'_>) -> Option<ScatterRecord> {
+        let scattered_ray = ray.next(hit.point, Vec3::random_in_unit_sphere());
         Some(ScatterRecord {
             ray: scattered_ray,
             color: self.color.clone(),
+            pdf: Some(0.25 / PI),
         })
     }
+
+    fn bsdf(&self, _ray: &Ray, _hit: &HitRecord<'_>, _direction: &Vec3) -> Vec3 {
+        Vec3::from(self.color.clone()) * (0.25 / PI)
+    }
+
+    fn pdf(&self, _ray: &Ray, _hit: &HitRecord<'_>, _direction: &Vec3) -> f64 {
+        0.25 / PI
+    }
 }
@@ -5,6 +5,11 @@ use crate::{
     texture::Texture,
 };
 
+/// How to generate scattered direction.
+///
+/// Only `True` is cosine weighted and matches Lambertian BSDF exactly,
+/// `Approximates` has density `2cos^3/PI`. Scattered color is always the albedo, so BSDF of
+/// other types is albedo times their density, not a true Lambertian one.
 #[derive(Debug, Clone)]
 pub enum LambertianMathType {
     Approximates,
@@ -16,21 +21,22 @@ impl LambertianMathType {
     #[must_use]
     pub fn scatter_ray(&self, ray: &Ray, hit: HitRecord<'_>) -> Ray {
         match self {
-            Self::Approximates => Ray::new(
-                hit.point,
-                hit.normal + Vec3::random_in_unit_sphere(),
-                ray.departure_time,
-            ),
-            Self::True => Ray::new(
-                hit.point,
-                hit.normal + Vec3::random_unit(),
-                ray.departure_time,
-            ),
-            Self::Hemisphere => Ray::new(
-                hit.point,
-                Vec3::random_unit_dir(&hit.normal),
-                ray.departure_time,
-            ),
+            Self::Approximates => ray.next(hit.point, hit.normal + Vec3::random_in_unit_sphere()),
+            Self::True => ray.next(hit.point, hit.normal + Vec3::random_unit()),
+            Self::Hemisphere => ray.next(hit.point, Vec3::random_unit_dir(&hit.normal)),
+        }
+    }
+
+    #[must_use]
+    pub fn pdf(&self, normal: &Vec3, direction: &Vec3) -> f64 {
+        let cos = normal.dot(&direction.unit());
+        if cos <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            Self::Approximates => 2.0 * cos * cos * cos / PI,
+            Self::True => cos / PI,
+            Self::Hemisphere => 0.5 / PI,
         }
     }
 }
@@ -60,10 +66,27 @@ impl<T: Texture> Lambertian<T> {
 impl<T: Texture> Material for Lambertian<T> {
     fn scatter(&self, ray: &Ray, hit: HitRecord<'_>) -> Option<ScatterRecord> {
         let color = self.texture.color(hit.u, hit.v, &hit.point);
+        let normal = hit.normal.clone();
         let new_ray = self.math_type.scatter_ray(ray, hit);
+        let pdf = self.math_type.pdf(&normal, &new_ray.direction);
         Some(ScatterRecord {
             color,
             ray: new_ray,
+            pdf: Some(pdf),
         })
     }
+
+    // Same as scatter, whose color albedo is bsdf * cos / pdf
+    fn bsdf(&self, _ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> Vec3 {
+        let pdf = self.math_type.pdf(&hit.normal, direction);
+        if pdf <= 0.0 {
+            return Vec3::default();
+        }
+        let albedo: Vec3 = self.texture.color(hit.u, hit.v, &hit.point).into();
+        albedo * pdf
+    }
+
+    fn pdf(&self, _ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        self.math_type.pdf(&hit.normal, direction)
+    }
 }
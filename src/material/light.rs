@@ -11,6 +11,15 @@ pub struct DiffuseLight<T> {
     multiplier: f64,
 }
 
+impl DiffuseLight<Color> {
+    /// Light of blackbody color at the temperature in kelvin, whose luminance is 1 before
+    /// multiplied, so it can be set by `nits`
+    #[must_use]
+    pub fn temperature(temperature: f64) -> Self {
+        Self::new(Spectrum::blackbody(temperature).into())
+    }
+}
+
 impl<T> DiffuseLight<T> {
     pub const fn new(texture: T) -> Self {
         Self {
@@ -23,6 +32,14 @@ impl<T> DiffuseLight<T> {
         self.multiplier = multiplier;
         self
     }
+
+    /// Set multiplier so a white texture emits the luminance in cd/m^2
+    #[must_use]
+    pub fn nits(mut self, luminance: f64) -> Self {
+        debug_assert!(luminance >= 0.0, "luminance = {}", luminance);
+        self.multiplier = luminance / NITS_PER_UNIT;
+        self
+    }
 }
 
 impl<T: Texture> Material for DiffuseLight<T> {
@@ -33,4 +50,8 @@ impl<T: Texture> Material for DiffuseLight<T> {
     fn emitted(&self, u: f64, v: f64, point: &Point3) -> Option<Vec3> {
         Some(<Color as Into<Vec3>>::into(self.texture.color(u, v, point)) * self.multiplier)
     }
+
+    fn emissive(&self) -> bool {
+        true
+    }
 }
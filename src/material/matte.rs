@@ -0,0 +1,82 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Lambertian, Material, ScatterRecord},
+    prelude::*,
+    texture::Texture,
+};
+
+/// Role of a material in output with alpha channel, for compositing renders onto photos
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Matte {
+    /// Transparent hole in the picture
+    Holdout,
+    /// Transparent except shadows cast on it, and reflections of objects by the amount.
+    /// Without sampled lights, shadows are where background is occluded by objects
+    ShadowCatcher(f64),
+}
+
+/// Object cut out of the picture, so things in the photo can be in front of rendered ones.
+///
+/// It's transparent in alpha output, black otherwise, and still blocks light and rays
+#[derive(Debug, Clone, Default)]
+pub struct Holdout;
+
+impl Material for Holdout {
+    fn scatter(&self, _ray: &Ray, _hit: HitRecord<'_>) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn matte(&self) -> Option<Matte> {
+        Some(Matte::Holdout)
+    }
+}
+
+/// Stand-in of the ground in a photo, only shadows and reflections cast on it are visible
+/// in alpha output.
+///
+/// Otherwise, and for light bouncing off it to objects, it's a Lambertian surface of the texture
+#[derive(Debug, Clone)]
+pub struct ShadowCatcher<T: Texture> {
+    surface: Lambertian<T>,
+    reflection: f64,
+}
+
+impl<T: Texture> ShadowCatcher<T> {
+    #[must_use]
+    pub fn new(texture: T) -> Self {
+        Self {
+            surface: Lambertian::new(texture),
+            reflection: 0.0,
+        }
+    }
+
+    /// Strength of mirror reflections of objects on it, default is 0
+    #[must_use]
+    pub fn reflection(mut self, reflection: f64) -> Self {
+        debug_assert!(
+            (0.0..=1.0).contains(&reflection),
+            "reflection = {}",
+            reflection
+        );
+        self.reflection = reflection;
+        self
+    }
+}
+
+impl<T: Texture> Material for ShadowCatcher<T> {
+    fn scatter(&self, ray: &Ray, hit: HitRecord<'_>) -> Option<ScatterRecord> {
+        self.surface.scatter(ray, hit)
+    }
+
+    fn bsdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> Vec3 {
+        self.surface.bsdf(ray, hit, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        self.surface.pdf(ray, hit, direction)
+    }
+
+    fn matte(&self) -> Option<Matte> {
+        Some(Matte::ShadowCatcher(self.reflection))
+    }
+}
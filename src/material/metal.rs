@@ -23,11 +23,34 @@ impl<T: Texture> Metal<T> {
         self
     }
 
-    fn reflect(&self, ray: &Ray, hit: &HitRecord<'_>) -> Ray {
+    fn mirror(ray: &Ray, hit: &HitRecord<'_>) -> Vec3 {
         let dir = ray.direction.unit();
-        let mut reflected_dir = &dir - 2.0 * dir.dot(&hit.normal) * &hit.normal;
-        reflected_dir += self.fuzz * Vec3::random_in_unit_sphere();
-        Ray::new(hit.point.clone(), reflected_dir, ray.departure_time)
+        &dir - 2.0 * dir.dot(&hit.normal) * &hit.normal
+    }
+
+    fn reflect(&self, ray: &Ray, hit: &HitRecord<'_>) -> Ray {
+        let reflected_dir = Self::mirror(ray, hit) + self.fuzz * Vec3::random_in_unit_sphere();
+        ray.next(hit.point.clone(), reflected_dir)
+    }
+
+    // Reflected direction is mirror direction plus a random point in ball of radius fuzz,
+    // so density of a direction is volume of the ball it passes through, in spherical coordinate
+    fn fuzz_pdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        let direction = direction.unit();
+        if self.delta() || direction.dot(&hit.normal) <= 0.0 {
+            return 0.0;
+        }
+        let cos = direction.dot(&Self::mirror(ray, hit));
+        let discriminant = self.fuzz.mul_add(self.fuzz, cos.mul_add(cos, -1.0));
+        if discriminant <= 0.0 {
+            return 0.0;
+        }
+        let far = cos + discriminant.sqrt();
+        let near = (cos - discriminant.sqrt()).max(0.0);
+        if far <= 0.0 {
+            return 0.0;
+        }
+        (far.powi(3) - near.powi(3)) / (4.0 * PI * self.fuzz.powi(3))
     }
 }
 
@@ -36,12 +59,36 @@ impl<T: Texture> Material for Metal<T> {
         let color = self.texture.color(hit.u, hit.v, &hit.point);
         let reflected = self.reflect(ray, &hit);
         if reflected.direction.dot(&hit.normal) > 0.0 {
+            let pdf = if self.delta() {
+                None
+            } else {
+                Some(self.fuzz_pdf(ray, &hit, &reflected.direction))
+            };
             Some(ScatterRecord {
                 color,
                 ray: reflected,
+                pdf,
             })
         } else {
             None
         }
     }
+
+    fn bsdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> Vec3 {
+        let color: Vec3 = self.texture.color(hit.u, hit.v, &hit.point).into();
+        color * self.fuzz_pdf(ray, hit, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        self.fuzz_pdf(ray, hit, direction)
+    }
+
+    fn specular(&self) -> bool {
+        true
+    }
+
+    /// Only perfect mirror is delta, fuzzy metal can be sampled by lights
+    fn delta(&self) -> bool {
+        self.fuzz <= 0.0
+    }
 }
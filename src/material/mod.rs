@@ -7,28 +7,64 @@ pub(crate) mod dielectric;
 pub(crate) mod isotropic;
 pub(crate) mod lambertian;
 pub(crate) mod light;
+pub(crate) mod matte;
 pub(crate) mod metal;
+pub(crate) mod phase;
+pub(crate) mod volume;
 
 pub use {
-    dielectric::{Dielectric, Glass},
+    dielectric::{Dielectric, Dispersion, Glass},
     isotropic::Isotropic,
     lambertian::{Lambertian, LambertianMathType},
     light::DiffuseLight,
+    matte::{Holdout, Matte, ShadowCatcher},
     metal::Metal,
+    phase::{DoubleHenyeyGreenstein, HenyeyGreenstein, IsotropicPhase, PhaseFunction, Rayleigh},
+    volume::Volume,
 };
 
 #[derive(Debug)]
 pub struct ScatterRecord {
+    /// Weight of the scattered ray, which is bsdf * cos(theta) / pdf
     pub color: Color,
     pub ray: Ray,
+    /// Probability density of scattered direction in solid angle, None for specular scatter
+    pub pdf: Option<f64>,
 }
 
+#[allow(unused_variables)]
 pub trait Material: Send + Sync {
     fn scatter(&self, ray: &Ray, hit: HitRecord<'_>) -> Option<ScatterRecord>;
-    #[allow(unused_variables)]
     fn emitted(&self, u: f64, v: f64, point: &Point3) -> Option<Vec3> {
         None
     }
+    /// BSDF value multiplied by cos(theta) for light coming from direction and going along -ray,
+    /// always zero for specular materials
+    fn bsdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> Vec3 {
+        Vec3::default()
+    }
+    /// Probability density of `scatter` choosing the direction, in solid angle
+    fn pdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        0.0
+    }
+    /// Whether objects made of this material should be sampled as lights
+    fn emissive(&self) -> bool {
+        false
+    }
+    /// Whether scattered direction is (nearly) determined by incoming direction,
+    /// like mirror reflection and refraction
+    fn specular(&self) -> bool {
+        false
+    }
+    /// Whether scattered direction is exactly determined, so `bsdf` and `pdf` can't evaluate it
+    /// and it can't take part in light sampling. Default is the same as `specular`
+    fn delta(&self) -> bool {
+        self.specular()
+    }
+    /// Role in output with alpha channel, None for ordinary materials
+    fn matte(&self) -> Option<Matte> {
+        None
+    }
 }
 
 impl<M: Material> Material for Arc<M> {
@@ -39,10 +75,34 @@ impl<M: Material> Material for Arc<M> {
     fn emitted(&self, u: f64, v: f64, point: &Point3) -> Option<Vec3> {
         self.as_ref().emitted(u, v, point)
     }
+
+    fn bsdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> Vec3 {
+        self.as_ref().bsdf(ray, hit, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        self.as_ref().pdf(ray, hit, direction)
+    }
+
+    fn emissive(&self) -> bool {
+        self.as_ref().emissive()
+    }
+
+    fn specular(&self) -> bool {
+        self.as_ref().specular()
+    }
+
+    fn delta(&self) -> bool {
+        self.as_ref().delta()
+    }
+
+    fn matte(&self) -> Option<Matte> {
+        self.as_ref().matte()
+    }
 }
 
 pub(crate) fn reflect(ray: &Ray, hit: &HitRecord<'_>) -> Ray {
     let dir = ray.direction.unit();
     let reflected_dir = &dir - 2.0 * dir.dot(&hit.normal) * &hit.normal;
-    Ray::new(hit.point.clone(), reflected_dir, ray.departure_time)
+    ray.next(hit.point.clone(), reflected_dir)
 }
@@ -0,0 +1,133 @@
+use {crate::prelude::*, std::sync::Arc};
+
+/// Angular distribution of light scattered in participating media
+pub trait PhaseFunction: Send + Sync {
+    /// Probability density(in solid angle) of scattering to a direction, whose angle to the
+    /// travelling direction has the cosine
+    fn value(&self, cos: f64) -> f64;
+    /// Random scattered direction of light travelling along the unit direction,
+    /// distributed by `value`
+    fn sample(&self, direction: &Vec3) -> Vec3;
+}
+
+impl<P: PhaseFunction> PhaseFunction for Arc<P> {
+    fn value(&self, cos: f64) -> f64 {
+        self.as_ref().value(cos)
+    }
+
+    fn sample(&self, direction: &Vec3) -> Vec3 {
+        self.as_ref().sample(direction)
+    }
+}
+
+// Direction whose angle to the unit direction has the cosine, with a random azimuth
+fn around(direction: &Vec3, cos: f64) -> Vec3 {
+    let cos = clamp(cos, -1.0..=1.0);
+    let sin = (1.0 - cos * cos).sqrt();
+    let phi = 2.0 * PI * Random::normal();
+    ONB::new(direction).local(&Vec3::new(sin * phi.cos(), sin * phi.sin(), cos))
+}
+
+/// Scatter to all directions equally
+#[derive(Debug, Clone, Default)]
+pub struct IsotropicPhase;
+
+impl PhaseFunction for IsotropicPhase {
+    fn value(&self, _cos: f64) -> f64 {
+        0.25 / PI
+    }
+
+    fn sample(&self, _direction: &Vec3) -> Vec3 {
+        Vec3::random_unit()
+    }
+}
+
+/// Henyey-Greenstein phase function, asymmetry g > 0 scatters forward and g < 0 backward
+#[derive(Debug, Clone)]
+pub struct HenyeyGreenstein {
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    #[must_use]
+    pub fn new(g: f64) -> Self {
+        debug_assert!(-1.0 < g && g < 1.0, "g = {}", g);
+        Self { g }
+    }
+}
+
+impl PhaseFunction for HenyeyGreenstein {
+    fn value(&self, cos: f64) -> f64 {
+        let g = self.g;
+        let denominator = (2.0 * g).mul_add(-cos, g.mul_add(g, 1.0));
+        0.25 / PI * g.mul_add(-g, 1.0) / (denominator * denominator.sqrt())
+    }
+
+    fn sample(&self, direction: &Vec3) -> Vec3 {
+        let g = self.g;
+        let xi = Random::normal();
+        let cos = if g.abs() < 1e-3 {
+            2.0f64.mul_add(-xi, 1.0)
+        } else {
+            let s = g.mul_add(-g, 1.0) / (2.0 * g).mul_add(xi, 1.0 - g);
+            (g.mul_add(g, 1.0) - s * s) / (2.0 * g)
+        };
+        around(direction, cos)
+    }
+}
+
+/// Weighted mix of a forward and a backward Henyey-Greenstein lobe,
+/// for media like clouds which have both strong forward scattering and a back glow
+#[derive(Debug, Clone)]
+pub struct DoubleHenyeyGreenstein {
+    forward: HenyeyGreenstein,
+    backward: HenyeyGreenstein,
+    weight: f64,
+}
+
+impl DoubleHenyeyGreenstein {
+    /// `weight` is the fraction of forward lobe
+    #[must_use]
+    pub fn new(forward: f64, backward: f64, weight: f64) -> Self {
+        debug_assert!((0.0..=1.0).contains(&weight), "weight = {}", weight);
+        Self {
+            forward: HenyeyGreenstein::new(forward),
+            backward: HenyeyGreenstein::new(backward),
+            weight,
+        }
+    }
+}
+
+impl PhaseFunction for DoubleHenyeyGreenstein {
+    fn value(&self, cos: f64) -> f64 {
+        self.weight.mul_add(
+            self.forward.value(cos),
+            (1.0 - self.weight) * self.backward.value(cos),
+        )
+    }
+
+    fn sample(&self, direction: &Vec3) -> Vec3 {
+        if Random::normal() < self.weight {
+            self.forward.sample(direction)
+        } else {
+            self.backward.sample(direction)
+        }
+    }
+}
+
+/// Rayleigh scattering by particles much smaller than wavelength, like air molecules
+#[derive(Debug, Clone, Default)]
+pub struct Rayleigh;
+
+impl PhaseFunction for Rayleigh {
+    fn value(&self, cos: f64) -> f64 {
+        3.0 / (16.0 * PI) * cos.mul_add(cos, 1.0)
+    }
+
+    // Invert the cdf (cos^3 + 3cos + 4) / 8 by Cardano's formula
+    fn sample(&self, direction: &Vec3) -> Vec3 {
+        let a = 8.0f64.mul_add(Random::normal(), -4.0);
+        let u = (0.5 * (a + a.mul_add(a, 4.0).sqrt())).cbrt();
+        around(direction, u - 1.0 / u)
+    }
+}
@@ -0,0 +1,87 @@
+use {
+    crate::{
+        hittable::{medium::EmissionField, HitRecord},
+        material::{phase::PhaseFunction, Material, ScatterRecord},
+        prelude::*,
+    },
+    std::{
+        fmt::{Debug, Formatter},
+        sync::Arc,
+    },
+};
+
+/// Material of participating media, scatters by the phase function and optionally emits light
+#[derive(Clone)]
+pub struct Volume<P> {
+    color: Color,
+    phase: P,
+    emission: Option<Arc<dyn EmissionField>>,
+}
+
+impl<P: Debug> Debug for Volume<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Volume {{ color: {:?}, phase: {:?}, emissive: {} }}",
+            self.color,
+            self.phase,
+            self.emission.is_some()
+        ))
+    }
+}
+
+impl<P> Volume<P> {
+    #[must_use]
+    pub const fn new(color: Color, phase: P) -> Self {
+        Self {
+            color,
+            phase,
+            emission: None,
+        }
+    }
+
+    /// Same color and emission with another phase function
+    #[must_use]
+    pub fn phase<Q>(self, phase: Q) -> Volume<Q> {
+        Volume {
+            color: self.color,
+            phase,
+            emission: self.emission,
+        }
+    }
+
+    #[must_use]
+    pub fn emission<E: EmissionField + 'static>(mut self, emission: E) -> Self {
+        self.emission = Some(Arc::new(emission));
+        self
+    }
+}
+
+impl<P: PhaseFunction> Material for Volume<P> {
+    fn scatter(&self, ray: &Ray, hit: HitRecord<'_>) -> Option<ScatterRecord> {
+        let direction = ray.direction.unit();
+        let scattered = self.phase.sample(&direction);
+        let pdf = self.phase.value(direction.dot(&scattered));
+        Some(ScatterRecord {
+            ray: ray.next(hit.point, scattered),
+            color: self.color.clone(),
+            pdf: Some(pdf),
+        })
+    }
+
+    // Scatter events are sampled proportional to density, so emission per unit density
+    // is an estimate of light emitted along the ray
+    fn emitted(&self, _u: f64, _v: f64, point: &Point3) -> Option<Vec3> {
+        self.emission
+            .as_ref()
+            .map(|emission| emission.emission(point))
+    }
+
+    fn bsdf(&self, ray: &Ray, hit: &HitRecord<'_>, direction: &Vec3) -> Vec3 {
+        Vec3::from(self.color.clone()) * self.pdf(ray, hit, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, _hit: &HitRecord<'_>, direction: &Vec3) -> f64 {
+        self.phase
+            .value(ray.direction.unit().dot(&direction.unit()))
+    }
+}
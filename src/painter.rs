@@ -1,5 +1,6 @@
 use {
     crate::{internal::rayon_seq_iter::SeqForEach, prelude::*},
+    image::{DynamicImage, ImageFormat, RgbaImage},
     log::info,
     rayon::{prelude::*, ThreadPool, ThreadPoolBuilder},
     std::{
@@ -118,18 +119,40 @@ impl IndexMut<usize> for PPMImage {
     }
 }
 
+/// How color channels are stored along with alpha
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Color is not multiplied by alpha, as PNG expects
+    Straight,
+    /// Color is multiplied by alpha, ready for compositing by `over` operation
+    Premultiplied,
+}
+
 #[derive(Debug)]
 pub struct Painter {
     pub width: usize,
     pub height: usize,
     samples: usize,
-    gamma: bool,
+    color_space: ColorSpace,
+    // Gain of each channel
+    white_balance: Color,
+    alpha_mode: AlphaMode,
     threads: usize,
     parallel: bool,
 }
 
+// Where rendered rows go, PPM is written row by row, RGBA image is encoded when all rows arrive
+enum PainterOutput {
+    Ppm(BufWriter<Box<dyn Write>>),
+    Rgba {
+        file: BufWriter<File>,
+        format: ImageFormat,
+        pixels: Vec<u8>,
+    },
+}
+
 struct PainterOutputContext<'c> {
-    file: BufWriter<Box<dyn Write>>,
+    output: PainterOutput,
     cancel: &'c AtomicBool,
 }
 
@@ -139,16 +162,52 @@ impl Painter {
         Self {
             width,
             height,
-            gamma: true,
+            color_space: ColorSpace::SRGB,
+            white_balance: Color::new(1.0, 1.0, 1.0),
+            alpha_mode: AlphaMode::Straight,
             samples: 50,
             threads: 0,
             parallel: true,
         }
     }
 
+    /// Encode output by sRGB transfer function or not, same as setting color space to
+    /// `SRGB` or `LinearSRGB`
     #[must_use]
     pub const fn gamma(mut self, gamma: bool) -> Self {
-        self.gamma = gamma;
+        self.color_space = if gamma {
+            ColorSpace::SRGB
+        } else {
+            ColorSpace::LinearSRGB
+        };
+        self
+    }
+
+    /// Color space of output image, default is sRGB
+    #[must_use]
+    pub const fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Scale each channel so light of blackbody at the temperature in kelvin looks white,
+    /// like setting white balance of a camera
+    #[must_use]
+    pub fn white_balance(mut self, temperature: f64) -> Self {
+        let white = Spectrum::blackbody(temperature);
+        // Very low temperature has almost no blue, keep gain finite
+        self.white_balance = Color::new(
+            1.0 / white.x.max(1e-3),
+            1.0 / white.y.max(1e-3),
+            1.0 / white.z.max(1e-3),
+        );
+        self
+    }
+
+    /// How color is stored by `draw_rgba`, default is straight
+    #[must_use]
+    pub const fn alpha_mode(mut self, alpha_mode: AlphaMode) -> Self {
+        self.alpha_mode = alpha_mode;
         self
     }
 
@@ -202,15 +261,40 @@ impl Painter {
         Ok(file)
     }
 
+    // Path and format of RGBA image to save, which needs a path with PNG or BMP extension
+    fn rgba_format(path: Option<&Path>) -> std::io::Result<(&Path, ImageFormat)> {
+        let invalid =
+            |message: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+        let path = path.ok_or_else(|| invalid("path is required to save RGBA image".to_owned()))?;
+        match ImageFormat::from_path(path) {
+            Ok(format @ (ImageFormat::Png | ImageFormat::Bmp)) => Ok((path, format)),
+            _ => Err(invalid(format!(
+                "unsupported RGBA image format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    // Channel count of pixels decides the output, 3 for PPM and 4 for RGBA image
     fn create_output_context<'c>(
-        &self, path: Option<&Path>, cancel: &'c AtomicBool,
+        &self, path: Option<&Path>, channels: usize, cancel: &'c AtomicBool,
     ) -> std::io::Result<PainterOutputContext<'c>> {
-        let file = self.create_output_file(path)?;
-        Ok(PainterOutputContext { file, cancel })
+        let output = if channels == 4 {
+            let (path, format) = Self::rgba_format(path)?;
+            PainterOutput::Rgba {
+                file: BufWriter::new(File::create(path)?),
+                format,
+                pixels: Vec::with_capacity(self.width * self.height * channels),
+            }
+        } else {
+            PainterOutput::Ppm(self.create_output_file(path)?)
+        };
+        Ok(PainterOutputContext { output, cancel })
     }
 
     // TODO: make it return RGBInt type
-    fn render_pixel<F>(&self, row: usize, column: usize, uv_color: &F) -> (u8, u8, u8)
+    #[allow(clippy::cast_precision_loss)] // sample count is small enough in practice
+    fn render_pixel<F>(&self, row: usize, column: usize, uv_color: &F) -> [u8; 3]
     where
         F: Fn(f64, f64) -> Vec3 + Send + Sync,
     {
@@ -220,89 +304,141 @@ impl Painter {
                 uv_color(u, v)
             })
             .sum();
-        let color = color.into_color(self.samples, self.gamma);
-        let color = color.i();
-        (color.r, color.g, color.b)
+        let color = Color::from(color / self.samples as f64) * &self.white_balance;
+        let color = self.color_space.encode(&color).i();
+        [color.r, color.g, color.b]
+    }
+    // Radiance of samples are premultiplied by their alpha
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)] // alpha is in [0..1]
+    fn render_rgba_pixel<F>(&self, row: usize, column: usize, uv_sample: &F) -> [u8; 4]
+    where
+        F: Fn(f64, f64) -> (Vec3, f64) + Send + Sync,
+    {
+        let (color, alpha) = (0..self.samples)
+            .map(|_| {
+                let (u, v) = self.calculate_uv(row, column);
+                uv_sample(u, v)
+            })
+            .fold((Vec3::default(), 0.0), |(color, alpha), sample| {
+                (color + sample.0, alpha + sample.1)
+            });
+        let alpha = clamp(alpha / self.samples as f64, 0.0..=1.0);
+        if alpha <= 0.0 {
+            return [0, 0, 0, 0];
+        }
+        let color = Color::from(color / self.samples as f64) * &self.white_balance;
+        let straight = self.color_space.encode(&(color / alpha)).clamp();
+        let color = match self.alpha_mode {
+            AlphaMode::Straight => straight.i(),
+            AlphaMode::Premultiplied => (straight * alpha).i(),
+        };
+        [color.r, color.g, color.b, (alpha * 255.0) as u8]
     }
 
-    fn parallel_render_row<F>(
-        &self, row: usize, uv_color: &F, cancel: &AtomicBool,
-    ) -> Vec<(u8, u8, u8)>
+    fn parallel_render_row<P, const N: usize>(
+        &self, row: usize, pixel: &P, cancel: &AtomicBool,
+    ) -> Vec<u8>
     where
-        F: Fn(f64, f64) -> Vec3 + Send + Sync,
+        P: Fn(usize, usize) -> [u8; N] + Send + Sync,
     {
         (0..self.width)
-            .map(|column| {
-                if cancel.load(Ordering::Relaxed) {
-                    return (0, 0, 0);
-                }
-                self.render_pixel(row, column, &uv_color)
-            })
+            .take_while(|_| !cancel.load(Ordering::Relaxed))
+            .flat_map(|column| pixel(row, column))
             .collect::<Vec<_>>()
     }
 
-    fn seq_render_row<F>(&self, row: usize, uv_color: &F) -> Vec<(u8, u8, u8)>
+    fn seq_render_row<P, const N: usize>(&self, row: usize, pixel: &P) -> Vec<u8>
     where
-        F: Fn(f64, f64) -> Vec3 + Send + Sync,
+        P: Fn(usize, usize) -> [u8; N] + Send + Sync,
     {
         (0..self.width)
-            .map(|column| self.render_pixel(row, column, &uv_color))
+            .flat_map(|column| pixel(row, column))
             .collect::<Vec<_>>()
     }
 
-    fn parallel_render_row_iter<'c, F>(
-        &'c self, uv_color: F, cancel: &'c AtomicBool,
-    ) -> impl IndexedParallelIterator<Item = Vec<(u8, u8, u8)>> + 'c
+    fn parallel_render_row_iter<'c, P, const N: usize>(
+        &'c self, pixel: P, cancel: &'c AtomicBool,
+    ) -> impl IndexedParallelIterator<Item = Vec<u8>> + 'c
     where
-        F: Fn(f64, f64) -> Vec3 + Send + Sync + 'c,
+        P: Fn(usize, usize) -> [u8; N] + Send + Sync + 'c,
     {
         (0..self.height)
             .into_par_iter()
-            .map(move |row| self.parallel_render_row(row, &uv_color, cancel))
+            .map(move |row| self.parallel_render_row(row, &pixel, cancel))
     }
 
-    fn seq_render_row_iter<'c, F>(
-        &'c self, uv_color: F,
-    ) -> impl Iterator<Item = Vec<(u8, u8, u8)>> + 'c
+    fn seq_render_row_iter<'c, P, const N: usize>(
+        &'c self, pixel: P,
+    ) -> impl Iterator<Item = Vec<u8>> + 'c
     where
-        F: Fn(f64, f64) -> Vec3 + Send + Sync + 'c,
+        P: Fn(usize, usize) -> [u8; N] + Send + Sync + 'c,
     {
-        (0..self.height).map(move |row| self.seq_render_row(row, &uv_color))
+        (0..self.height).map(move |row| self.seq_render_row(row, &pixel))
     }
 
+    #[allow(clippy::cast_possible_truncation)] // because picture size is small enough in practice
     fn real_row_pixels_to_file(
-        context: &mut PainterOutputContext<'_>, pixels: Vec<(u8, u8, u8)>,
+        &self, context: &mut PainterOutputContext<'_>, pixels: Vec<u8>,
     ) -> std::io::Result<()> {
-        for pixel in pixels {
-            writeln!(context.file, "{} {} {}", pixel.0, pixel.1, pixel.2)?;
+        match &mut context.output {
+            PainterOutput::Ppm(file) => {
+                for pixel in pixels.chunks(3) {
+                    writeln!(file, "{} {} {}", pixel[0], pixel[1], pixel[2])?;
+                }
+                file.flush()
+            }
+            PainterOutput::Rgba {
+                file,
+                format,
+                pixels: all,
+            } => {
+                all.extend(pixels);
+                if all.len() < self.width * self.height * 4 {
+                    return Ok(());
+                }
+                let image =
+                    RgbaImage::from_raw(self.width as u32, self.height as u32, std::mem::take(all))
+                        .unwrap();
+                DynamicImage::ImageRgba8(image)
+                    .write_to(file, *format)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+                file.flush()
+            }
         }
-        context.file.flush()
     }
 
     fn row_pixels_to_file(
-        context: &mut PainterOutputContext<'_>, pixels: Vec<(u8, u8, u8)>,
+        &self, context: &mut PainterOutputContext<'_>, pixels: Vec<u8>,
     ) -> std::io::Result<()> {
-        Self::real_row_pixels_to_file(context, pixels).map_err(|e| {
+        self.real_row_pixels_to_file(context, pixels).map_err(|e| {
             context.cancel.store(true, Ordering::Relaxed);
             e
         })
     }
 
-    fn parallel_render_and_output<F>(&self, uv_color: F, path: Option<&Path>) -> std::io::Result<()>
+    fn parallel_render_and_output<P, const N: usize>(
+        &self, pixel: P, path: Option<&Path>,
+    ) -> std::io::Result<()>
     where
-        F: Fn(f64, f64) -> Vec3 + Send + Sync,
+        P: Fn(usize, usize) -> [u8; N] + Send + Sync,
     {
         let cancel = AtomicBool::new(false);
         let finished_row = AtomicUsize::new(0);
 
-        self.parallel_render_row_iter(uv_color, &cancel)
+        self.parallel_render_row_iter(pixel, &cancel)
             .inspect(|_| {
                 let count = finished_row.fetch_add(1, Ordering::Relaxed);
                 info!("Scan line remaining: {}", self.height - count - 1);
             })
             .seq_for_each_with(
-                || self.create_output_context(path, &cancel),
-                |context, pixels| Self::row_pixels_to_file(context, pixels),
+                || {
+                    // Rendering already started, stop it if output can't be created
+                    self.create_output_context(path, N, &cancel).map_err(|e| {
+                        cancel.store(true, Ordering::Relaxed);
+                        e
+                    })
+                },
+                |context, pixels| self.row_pixels_to_file(context, pixels),
             )
     }
 
@@ -318,33 +454,60 @@ impl Painter {
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
     }
 
-    /// # Errors
-    ///
-    /// When open or save to file failed
-    pub fn draw<P, F>(&self, path: &Option<P>, uv_color: F) -> std::io::Result<()>
+    fn draw_pixels<P, const N: usize>(&self, path: Option<&Path>, pixel: P) -> std::io::Result<()>
     where
-        P: AsRef<Path>,
-        F: Fn(f64, f64) -> Vec3 + Send + Sync,
+        P: Fn(usize, usize) -> [u8; N] + Send + Sync,
     {
-        let path = match path {
-            Some(ref path) => Some(path.as_ref()),
-            None => None,
-        };
-
         if self.parallel {
             let pool = self.setup_thread_pool()?;
 
             info!("Worker thread count: {}", pool.current_num_threads());
 
-            pool.install(|| self.parallel_render_and_output(uv_color, path))
+            pool.install(|| self.parallel_render_and_output(pixel, path))
         } else {
             let cancel = AtomicBool::new(false); // useless in parallel mode
-            let mut context = self.create_output_context(path, &cancel)?;
-            for (row, pixels) in self.seq_render_row_iter(uv_color).enumerate() {
+            let mut context = self.create_output_context(path, N, &cancel)?;
+            for (row, pixels) in self.seq_render_row_iter(pixel).enumerate() {
                 info!("Scan line remaining: {}", self.height - row);
-                Self::row_pixels_to_file(&mut context, pixels)?;
+                self.row_pixels_to_file(&mut context, pixels)?;
             }
             Ok(())
         }
     }
+
+    /// Render with alpha channel and save as RGBA image, whose format is decided by extension
+    /// of the path, only PNG and BMP are supported
+    ///
+    /// # Errors
+    ///
+    /// When path is None or has unsupported extension, checked before render,
+    /// or render or save to file failed
+    pub fn draw_rgba<P, F>(&self, path: &Option<P>, uv_sample: F) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+        F: Fn(f64, f64) -> (Vec3, f64) + Send + Sync,
+    {
+        let path = path.as_ref().map(AsRef::as_ref);
+        Self::rgba_format(path)?;
+        self.draw_pixels(path, |row, column| {
+            self.render_rgba_pixel(row, column, &uv_sample)
+        })
+    }
+
+    /// # Errors
+    ///
+    /// When open or save to file failed
+    pub fn draw<P, F>(&self, path: &Option<P>, uv_color: F) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+        F: Fn(f64, f64) -> Vec3 + Send + Sync,
+    {
+        let path = match path {
+            Some(ref path) => Some(path.as_ref()),
+            None => None,
+        };
+        self.draw_pixels(path, |row, column| {
+            self.render_pixel(row, column, &uv_color)
+        })
+    }
 }
@@ -3,32 +3,14 @@ use {
         prelude::{clamp, vec3::Point3},
         texture::Texture,
     },
-    std::{borrow::Cow, ops::Mul},
+    std::{
+        iter::Sum,
+        ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+    },
 };
 
-macro_rules! check0to1 {
-    ($r: ident, $g: ident, $b: ident) => {
-        debug_assert!((0.0_f64..=1.0_f64).contains(&$r), "r = {}", $r);
-        debug_assert!((0.0_f64..=1.0_f64).contains(&$g), "g = {}", $g);
-        debug_assert!((0.0_f64..=1.0_f64).contains(&$b), "b = {}", $b);
-    };
-}
-
-#[derive(Debug, Clone, Default)]
-pub struct RGBFloat {
-    pub r: f64,
-    pub g: f64,
-    pub b: f64,
-}
-
-impl RGBFloat {
-    pub fn new(r: f64, g: f64, b: f64) -> Self {
-        check0to1!(r, g, b);
-        Self { r, g, b }
-    }
-}
-
-#[derive(Debug, Clone, Default)]
+/// 8-bit color, only used as texture input and image output format
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct RGBInt {
     pub r: u8,
     pub g: u8,
@@ -36,76 +18,72 @@ pub struct RGBInt {
 }
 
 impl RGBInt {
+    #[must_use]
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
 }
 
-impl From<&RGBFloat> for RGBInt {
-    fn from(c: &RGBFloat) -> Self {
+impl From<&Color> for RGBInt {
+    fn from(c: &Color) -> Self {
         #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-        // because RGBFloat r g b should be in [0..1]
-        Self::new(
-            (c.r * 255.0) as u8,
-            (c.g * 255.0) as u8,
-            (c.b * 255.0) as u8,
-        )
+        // because value is clamped into [0..255]
+        let channel = |v: f64| (clamp(v, 0.0..=1.0) * 255.0) as u8;
+        Self::new(channel(c.r), channel(c.g), channel(c.b))
     }
 }
 
-impl From<&RGBInt> for RGBFloat {
+impl From<&RGBInt> for Color {
     fn from(c: &RGBInt) -> Self {
-        let s = 1.0 / 255.0;
-        Self::new(f64::from(c.r) * s, f64::from(c.g) * s, f64::from(c.b) * s)
+        Self::new_int(c.r, c.g, c.b)
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Color {
-    Float(RGBFloat),
-    Int(RGBInt),
+/// Linear RGB color, channels are not clamped so it can hold radiance brighter than white
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
 }
 
-impl Default for Color {
-    fn default() -> Self {
-        Self::Float(RGBFloat::default())
+impl Color {
+    #[must_use]
+    pub const fn new(r: f64, g: f64, b: f64) -> Self {
+        Self { r, g, b }
     }
-}
 
-impl Color {
     #[must_use]
-    pub const fn new_int(r: u8, g: u8, b: u8) -> Self {
-        Self::Int(RGBInt::new(r, g, b))
+    pub fn new_int(r: u8, g: u8, b: u8) -> Self {
+        let s = 1.0 / 255.0;
+        Self::new(f64::from(r) * s, f64::from(g) * s, f64::from(b) * s)
     }
 
+    /// 8-bit color, channels are clamped into [0..1] first
     #[must_use]
-    pub fn new(r: f64, g: f64, b: f64) -> Self {
-        Self::Float(RGBFloat::new(r, g, b))
+    pub fn i(&self) -> RGBInt {
+        self.into()
     }
 
+    /// Linear interpolation, `slide` = 0 gives self and 1 gives rhs
     #[must_use]
-    pub fn i(&self) -> Cow<'_, RGBInt> {
-        match self {
-            Self::Float(c) => Cow::Owned(c.into()),
-            Self::Int(c) => Cow::Borrowed(c),
-        }
+    pub fn lerp(&self, rhs: &Self, slide: f64) -> Self {
+        (1.0 - slide) * self + slide * rhs
     }
 
+    /// Relative luminance(Y) of Rec.709 primaries
     #[must_use]
-    pub fn f(&self) -> Cow<'_, RGBFloat> {
-        match self {
-            Self::Float(c) => Cow::Borrowed(c),
-            Self::Int(c) => Cow::Owned(c.into()),
-        }
+    pub fn luminance(&self) -> f64 {
+        0.0722f64.mul_add(self.b, 0.2126f64.mul_add(self.r, 0.7152 * self.g))
     }
 
     #[must_use]
-    pub fn gradient(&self, rhs: &Self, slide: f64) -> Self {
-        let a = (1.0 - slide) * self;
-        let b = slide * rhs;
-        let c1 = a.f();
-        let c2 = b.f();
-        Self::new(c1.r + c2.r, c1.g + c2.g, c1.b + c2.b)
+    pub fn clamp(&self) -> Self {
+        Self::new(
+            clamp(self.r, 0.0..=1.0),
+            clamp(self.g, 0.0..=1.0),
+            clamp(self.b, 0.0..=1.0),
+        )
     }
 }
 
@@ -115,45 +93,63 @@ impl Texture for Color {
     }
 }
 
-impl Mul<&Color> for &Color {
-    type Output = Color;
-    fn mul(self, rhs: &Color) -> Self::Output {
-        let c1 = self.f();
-        let c2 = rhs.f();
-        Color::new(c1.r * c2.r, c1.g * c2.g, c1.b * c2.b)
-    }
+// Implement the operator for owned and borrowed operands from the borrowed one
+macro_rules! forward_binary_op {
+    ($trait: ident, $method: ident) => {
+        impl $trait<Color> for &Color {
+            type Output = Color;
+            fn $method(self, rhs: Color) -> Self::Output {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $trait<&Color> for Color {
+            type Output = Self;
+            fn $method(self, rhs: &Self) -> Self::Output {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $trait<Color> for Color {
+            type Output = Self;
+            fn $method(self, rhs: Self) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        }
+    };
 }
 
-impl Mul<Color> for &Color {
+impl Add<&Color> for &Color {
     type Output = Color;
-    fn mul(self, rhs: Color) -> Self::Output {
-        self * &rhs
+    fn add(self, rhs: &Color) -> Self::Output {
+        Color::new(self.r + rhs.r, self.g + rhs.g, self.b + rhs.b)
     }
 }
 
-impl Mul<&Color> for Color {
-    type Output = Self;
-    fn mul(self, rhs: &Self) -> Self::Output {
-        &self * rhs
+forward_binary_op!(Add, add);
+
+impl Sub<&Color> for &Color {
+    type Output = Color;
+    fn sub(self, rhs: &Color) -> Self::Output {
+        Color::new(self.r - rhs.r, self.g - rhs.g, self.b - rhs.b)
     }
 }
 
-impl Mul<Color> for Color {
-    type Output = Self;
-    fn mul(self, rhs: Self) -> Self::Output {
-        &self * &rhs
+forward_binary_op!(Sub, sub);
+
+impl Mul<&Color> for &Color {
+    type Output = Color;
+    fn mul(self, rhs: &Color) -> Self::Output {
+        Color::new(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b)
     }
 }
 
+forward_binary_op!(Mul, mul);
+
 impl Mul<f64> for &Color {
     type Output = Color;
     fn mul(self, rhs: f64) -> Self::Output {
-        let c = self.f();
-        Color::new(
-            clamp(c.r * rhs, 0.0..=1.0),
-            clamp(c.g * rhs, 0.0..=1.0),
-            clamp(c.b * rhs, 0.0..=1.0),
-        )
+        Color::new(self.r * rhs, self.g * rhs, self.b * rhs)
     }
 }
 
@@ -170,9 +166,86 @@ impl Mul<&Color> for f64 {
         rhs * self
     }
 }
+
 impl Mul<Color> for f64 {
     type Output = Color;
     fn mul(self, rhs: Color) -> Self::Output {
         &rhs * self
     }
 }
+
+impl Div<f64> for &Color {
+    type Output = Color;
+    fn div(self, rhs: f64) -> Self::Output {
+        self * (1.0 / rhs)
+    }
+}
+
+impl Div<f64> for Color {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self::Output {
+        &self / rhs
+    }
+}
+
+impl AddAssign<&Self> for Color {
+    fn add_assign(&mut self, rhs: &Self) {
+        self.r += rhs.r;
+        self.g += rhs.g;
+        self.b += rhs.b;
+    }
+}
+
+impl AddAssign<Self> for Color {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs;
+    }
+}
+
+impl SubAssign<&Self> for Color {
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.r -= rhs.r;
+        self.g -= rhs.g;
+        self.b -= rhs.b;
+    }
+}
+
+impl SubAssign<Self> for Color {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self -= &rhs;
+    }
+}
+
+impl MulAssign<&Self> for Color {
+    fn mul_assign(&mut self, rhs: &Self) {
+        self.r *= rhs.r;
+        self.g *= rhs.g;
+        self.b *= rhs.b;
+    }
+}
+
+impl MulAssign<Self> for Color {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self *= &rhs;
+    }
+}
+
+impl MulAssign<f64> for Color {
+    fn mul_assign(&mut self, rhs: f64) {
+        self.r *= rhs;
+        self.g *= rhs;
+        self.b *= rhs;
+    }
+}
+
+impl DivAssign<f64> for Color {
+    fn div_assign(&mut self, rhs: f64) {
+        *self *= 1.0 / rhs;
+    }
+}
+
+impl Sum for Color {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, val| acc + val)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::prelude::Color;
+
+// Gamut conversions from and to linear sRGB(Rec.709 primaries, D65 white), the working
+// space of rendering. ACEScg has D60 white, so they include a Bradford adaptation
+const ACESCG_TO_LINEAR: [[f64; 3]; 3] = [
+    [1.705_051_0, -0.621_792_1, -0.083_258_9],
+    [-0.130_256_4, 1.140_804_7, -0.010_548_3],
+    [-0.024_003_4, -0.128_969_0, 1.152_972_3],
+];
+const LINEAR_TO_ACESCG: [[f64; 3]; 3] = invert(&ACESCG_TO_LINEAR);
+const DISPLAY_P3_TO_LINEAR: [[f64; 3]; 3] = [
+    [1.224_940_2, -0.224_940_2, 0.0],
+    [-0.042_056_9, 1.042_056_9, 0.0],
+    [-0.019_637_6, -0.078_636_1, 1.098_273_7],
+];
+const LINEAR_TO_DISPLAY_P3: [[f64; 3]; 3] = invert(&DISPLAY_P3_TO_LINEAR);
+
+const fn minor(m: &[[f64; 3]; 3], rows: (usize, usize), columns: (usize, usize)) -> f64 {
+    m[rows.0][columns.0] * m[rows.1][columns.1] - m[rows.0][columns.1] * m[rows.1][columns.0]
+}
+
+// Inverse of 3x3 matrix by cofactors, so conversions of both directions round trip exactly
+const fn invert(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut inverse = [
+        [
+            minor(m, (1, 2), (1, 2)),
+            minor(m, (0, 2), (2, 1)),
+            minor(m, (0, 1), (1, 2)),
+        ],
+        [
+            minor(m, (1, 2), (2, 0)),
+            minor(m, (0, 2), (0, 2)),
+            minor(m, (0, 1), (2, 0)),
+        ],
+        [
+            minor(m, (1, 2), (0, 1)),
+            minor(m, (0, 2), (1, 0)),
+            minor(m, (0, 1), (0, 1)),
+        ],
+    ];
+    let determinant = m[0][0] * inverse[0][0] + m[0][1] * inverse[1][0] + m[0][2] * inverse[2][0];
+    let mut i = 0;
+    while i < 9 {
+        inverse[i / 3][i % 3] /= determinant;
+        i += 1;
+    }
+    inverse
+}
+
+fn transform(matrix: &[[f64; 3]; 3], c: &Color) -> Color {
+    let row = |m: &[f64; 3]| m[0].mul_add(c.r, m[1].mul_add(c.g, m[2] * c.b));
+    Color::new(row(&matrix[0]), row(&matrix[1]), row(&matrix[2]))
+}
+
+// sRGB transfer function, odd extended so negative values out of gamut survive round trip
+fn srgb_decode(v: f64) -> f64 {
+    let a = v.abs();
+    let linear = if a <= 0.040_45 {
+        a / 12.92
+    } else {
+        ((a + 0.055) / 1.055).powf(2.4)
+    };
+    linear.copysign(v)
+}
+
+fn srgb_encode(v: f64) -> f64 {
+    let a = v.abs();
+    let encoded = if a <= 0.003_130_8 {
+        a * 12.92
+    } else {
+        1.055f64.mul_add(a.powf(1.0 / 2.4), -0.055)
+    };
+    encoded.copysign(v)
+}
+
+/// Color space of texture inputs and image outputs, colors inside the renderer are always
+/// linear sRGB
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// sRGB primaries with sRGB transfer function, most 8-bit images are in it
+    #[default]
+    SRGB,
+    /// sRGB(Rec.709) primaries without transfer function
+    LinearSRGB,
+    /// Linear ACES AP1 primaries, common working space of VFX pipelines
+    ACEScg,
+    /// Display P3 primaries with sRGB transfer function, used by wide gamut displays
+    DisplayP3,
+}
+
+impl ColorSpace {
+    /// Whether values are stored without a transfer function
+    #[must_use]
+    pub const fn is_linear(self) -> bool {
+        matches!(self, Self::LinearSRGB | Self::ACEScg)
+    }
+
+    /// Convert color stored in this space to linear sRGB
+    #[must_use]
+    pub fn decode(self, c: &Color) -> Color {
+        let linear = if self.is_linear() {
+            c.clone()
+        } else {
+            Color::new(srgb_decode(c.r), srgb_decode(c.g), srgb_decode(c.b))
+        };
+        match self {
+            Self::SRGB | Self::LinearSRGB => linear,
+            Self::ACEScg => transform(&ACESCG_TO_LINEAR, &linear),
+            Self::DisplayP3 => transform(&DISPLAY_P3_TO_LINEAR, &linear),
+        }
+    }
+
+    /// Convert linear sRGB color to be stored in this space
+    #[must_use]
+    pub fn encode(self, c: &Color) -> Color {
+        let linear = match self {
+            Self::SRGB | Self::LinearSRGB => c.clone(),
+            Self::ACEScg => transform(&LINEAR_TO_ACESCG, c),
+            Self::DisplayP3 => transform(&LINEAR_TO_DISPLAY_P3, c),
+        };
+        if self.is_linear() {
+            linear
+        } else {
+            Color::new(
+                srgb_encode(linear.r),
+                srgb_encode(linear.g),
+                srgb_encode(linear.b),
+            )
+        }
+    }
+}
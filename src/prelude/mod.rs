@@ -1,16 +1,22 @@
 mod aabb;
 mod color;
+mod color_space;
+mod onb;
 mod random;
 mod ray;
+mod spectrum;
 mod vec3;
 
 use std::ops::{Bound, RangeBounds};
 
 pub use {
     aabb::AABB,
-    color::Color,
+    color::{Color, RGBInt},
+    color_space::ColorSpace,
+    onb::ONB,
     random::{Random, SeedRandom},
-    ray::Ray,
+    ray::{Ray, RayKind},
+    spectrum::{Spectrum, NITS_PER_UNIT, WAVELENGTH_RANGE},
     std::f64::consts::PI,
     vec3::{Point3, Vec3},
 };
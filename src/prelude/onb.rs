@@ -0,0 +1,30 @@
+use crate::prelude::vec3::Vec3;
+
+/// Orthonormal basis, w is the given direction
+#[derive(Debug, Clone)]
+pub struct ONB {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl ONB {
+    #[must_use]
+    pub fn new(w: &Vec3) -> Self {
+        let w = w.unit();
+        let a = if w.x.abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = w.cross(&a).unit();
+        let u = w.cross(&v);
+        Self { u, v, w }
+    }
+
+    /// Convert local coordinate to world coordinate
+    #[must_use]
+    pub fn local(&self, a: &Vec3) -> Vec3 {
+        &self.u * a.x + &self.v * a.y + &self.w * a.z
+    }
+}
@@ -1,24 +1,89 @@
-use crate::prelude::vec3::{Point3, Vec3};
+use crate::prelude::{
+    spectrum::Spectrum,
+    vec3::{Point3, Vec3},
+};
 
-#[derive(Debug)]
+/// What a ray is traced for, objects can be made invisible to some kinds of rays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RayKind {
+    /// Starts from the camera
+    Camera,
+    /// Scattered by surfaces or media, like reflection and refraction, or emitted from lights
+    Reflection,
+    /// Tests whether light is blocked between two points
+    Shadow,
+    /// Finds the light a sampled direction reaches, only lights are hit by it
+    Light,
+}
+
+#[derive(Debug, Clone)]
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
     pub departure_time: f64,
+    /// Wavelength in nanometers carried in spectral rendering
+    pub wavelength: Option<f64>,
+    pub kind: RayKind,
 }
 
 impl Ray {
+    /// Kind of the new ray is camera, set it by `kind` for other uses
     #[must_use]
     pub const fn new(origin: Point3, direction: Vec3, departure_time: f64) -> Self {
         Self {
             origin,
             direction,
             departure_time,
+            wavelength: None,
+            kind: RayKind::Camera,
         }
     }
 
+    #[must_use]
+    pub const fn wavelength(mut self, wavelength: Option<f64>) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
+
+    #[must_use]
+    pub const fn kind(mut self, kind: RayKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// New ray scattered from origin to direction, departs at the same time with the same
+    /// wavelength, its kind is reflection
+    #[must_use]
+    pub const fn next(&self, origin: Point3, direction: Vec3) -> Self {
+        Self::new(origin, direction, self.departure_time)
+            .wavelength(self.wavelength)
+            .kind(RayKind::Reflection)
+    }
+
+    /// The same ray described by another origin and direction, like in the local space of
+    /// a transformed object
+    #[must_use]
+    pub const fn transformed(&self, origin: Point3, direction: Vec3) -> Self {
+        Self::new(origin, direction, self.departure_time)
+            .wavelength(self.wavelength)
+            .kind(self.kind)
+    }
+
     #[must_use]
     pub fn position_after(&self, unit: f64) -> Point3 {
         &self.origin + &self.direction * unit
     }
+
+    /// Color carried by this ray, in spectral rendering it's the spectral value at the wavelength
+    /// in all channels
+    #[must_use]
+    pub fn spectrum(&self, color: Vec3) -> Vec3 {
+        match self.wavelength {
+            Some(wavelength) => {
+                let value = Spectrum::from_rgb(&color, wavelength);
+                Vec3::new(value, value, value)
+            }
+            None => color,
+        }
+    }
 }
@@ -0,0 +1,172 @@
+use {
+    crate::prelude::{clamp, vec3::Vec3, Random},
+    once_cell::sync::Lazy,
+    std::ops::Range,
+};
+
+/// Wavelengths(in nanometers) sampled by spectral rendering
+pub const WAVELENGTH_RANGE: Range<f64> = 380.0..780.0;
+
+/// Luminance(in cd/m^2, or nits) of radiance 1, lights in physical units are divided by it
+pub const NITS_PER_UNIT: f64 = 10_000.0;
+
+// Basis spectra of Smits' RGB to spectrum conversion, 10 bins from 380nm to 720nm
+const BIN_START: f64 = 380.0;
+const BIN_WIDTH: f64 = 34.0;
+const WHITE: [f64; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const CYAN: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const MAGENTA: [f64; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const YELLOW: [f64; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const RED: [f64; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const GREEN: [f64; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const BLUE: [f64; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+// Integral of y color matching function, and linear sRGB of constant spectrum 1
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)] // wavelengths are small positive numbers
+static RESPONSE: Lazy<(f64, Vec3)> = Lazy::new(|| {
+    let mut y = 0.0;
+    let mut white = Vec3::default();
+    for nm in (WAVELENGTH_RANGE.start as usize)..(WAVELENGTH_RANGE.end as usize) {
+        let xyz = Spectrum::cie_xyz(nm as f64 + 0.5);
+        y += xyz.y;
+        white += Spectrum::xyz_to_rgb(&xyz);
+    }
+    (y, white / y)
+});
+
+/// Conversions between spectra and colors, used by spectral rendering
+#[derive(Debug)]
+pub struct Spectrum;
+
+impl Spectrum {
+    /// Uniformly sample a wavelength in `WAVELENGTH_RANGE`
+    #[must_use]
+    pub fn sample_wavelength() -> f64 {
+        Random::range(WAVELENGTH_RANGE)
+    }
+
+    // Linear interpolation between bin centers
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    // position is clamped into [0, 9]
+    fn bin(values: &[f64; 10], wavelength: f64) -> f64 {
+        let position = clamp((wavelength - BIN_START) / BIN_WIDTH - 0.5, 0.0..=9.0);
+        let index = (position as usize).min(8);
+        let t = position - index as f64;
+        (1.0 - t).mul_add(values[index], t * values[index + 1])
+    }
+
+    /// Value at the wavelength of a smooth spectrum whose color is the linear rgb,
+    /// by Smits' method
+    #[must_use]
+    pub fn from_rgb(rgb: &Vec3, wavelength: f64) -> f64 {
+        let (r, g, b) = (rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0));
+        let at = |values: &[f64; 10]| Self::bin(values, wavelength);
+        // white part, then the secondary color and the primary color
+        let (white, (secondary, second), (primary, first)) = if r <= g && r <= b {
+            if g <= b {
+                (r, (g - r, &CYAN), (b - g, &BLUE))
+            } else {
+                (r, (b - r, &CYAN), (g - b, &GREEN))
+            }
+        } else if g <= r && g <= b {
+            if r <= b {
+                (g, (r - g, &MAGENTA), (b - r, &BLUE))
+            } else {
+                (g, (b - g, &MAGENTA), (r - b, &RED))
+            }
+        } else if r <= g {
+            (b, (r - b, &YELLOW), (g - r, &GREEN))
+        } else {
+            (b, (g - b, &YELLOW), (r - g, &RED))
+        };
+        white.mul_add(
+            at(&WHITE),
+            secondary.mul_add(at(second), primary * at(first)),
+        )
+    }
+
+    /// CIE 1931 color matching functions, by the multi-lobe fit of Wyman et al.
+    #[must_use]
+    pub fn cie_xyz(wavelength: f64) -> Vec3 {
+        let g = |mu: f64, sigma1: f64, sigma2: f64| {
+            let t = (wavelength - mu) / if wavelength < mu { sigma1 } else { sigma2 };
+            (-0.5 * t * t).exp()
+        };
+        Vec3::new(
+            1.056f64.mul_add(
+                g(599.8, 37.9, 31.0),
+                0.362f64.mul_add(g(442.0, 16.0, 26.7), -0.065 * g(501.1, 20.4, 26.2)),
+            ),
+            0.821f64.mul_add(g(568.8, 46.9, 40.5), 0.286 * g(530.9, 16.3, 31.1)),
+            1.217f64.mul_add(g(437.0, 11.8, 36.0), 0.681 * g(459.0, 26.0, 13.8)),
+        )
+    }
+
+    /// Convert CIE XYZ to linear sRGB
+    #[must_use]
+    pub fn xyz_to_rgb(xyz: &Vec3) -> Vec3 {
+        Vec3::new(
+            3.2406f64.mul_add(xyz.x, (-1.5372f64).mul_add(xyz.y, -0.4986 * xyz.z)),
+            (-0.9689f64).mul_add(xyz.x, 1.8758f64.mul_add(xyz.y, 0.0415 * xyz.z)),
+            0.0557f64.mul_add(xyz.x, (-0.2040f64).mul_add(xyz.y, 1.0570 * xyz.z)),
+        )
+    }
+
+    /// Linear sRGB estimated from spectral value at a wavelength sampled by `sample_wavelength`.
+    ///
+    /// Result is white balanced, so average of spectrum from `from_rgb` converts back to the rgb
+    #[must_use]
+    pub fn to_rgb(value: f64, wavelength: f64) -> Vec3 {
+        let (y, white) = &*RESPONSE;
+        let length = WAVELENGTH_RANGE.end - WAVELENGTH_RANGE.start;
+        let rgb = Self::xyz_to_rgb(&Self::cie_xyz(wavelength)) * (value * length / y);
+        Vec3::new(rgb.x / white.x, rgb.y / white.y, rgb.z / white.z)
+    }
+
+    /// Linear sRGB of black body radiation at the temperature(in kelvin), normalized to luminance 1
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )] // wavelengths are small positive numbers
+    pub fn blackbody(temperature: f64) -> Vec3 {
+        debug_assert!(temperature > 0.0, "temperature = {}", temperature);
+        let mut xyz = Vec3::default();
+        for nm in (WAVELENGTH_RANGE.start as usize)..(WAVELENGTH_RANGE.end as usize) {
+            let wavelength = nm as f64 + 0.5;
+            // Planck's law without constant factor, second radiation constant in nm*K
+            let radiance =
+                1.0 / (wavelength.powi(5) * (1.438_777e7 / (wavelength * temperature)).exp_m1());
+            xyz += Self::cie_xyz(wavelength) * radiance;
+        }
+        if xyz.y <= 0.0 {
+            return Vec3::default();
+        }
+        let y = xyz.y;
+        let rgb = Self::xyz_to_rgb(&(xyz / y));
+        Vec3::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    crate::prelude::{clamp, Color, Random, PI},
+    crate::prelude::{Color, Random, PI},
     std::{
         fmt::Display,
         iter::Sum,
@@ -99,6 +99,17 @@ impl Vec3 {
         }
     }
 
+    /// Random direction inside the cone around z axis, evenly distributed in solid angle
+    #[must_use]
+    pub fn random_in_cone(cos_theta_max: f64) -> Self {
+        let r1 = Random::normal();
+        let r2 = Random::normal();
+        let z = r2.mul_add(cos_theta_max - 1.0, 1.0);
+        let phi = 2.0 * PI * r1;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        Self::new(phi.cos() * r, phi.sin() * r, z)
+    }
+
     #[must_use]
     pub fn random_unit_disk() -> Self {
         loop {
@@ -144,22 +155,6 @@ impl Vec3 {
     pub fn unit(&self) -> Self {
         self / self.length()
     }
-
-    #[allow(clippy::cast_precision_loss)] // sample count is small enough in practice
-    #[must_use]
-    pub fn into_color(mut self, sample_count: usize, gamma: bool) -> Color {
-        self /= sample_count as f64;
-        if gamma {
-            self.x = self.x.sqrt();
-            self.y = self.y.sqrt();
-            self.z = self.z.sqrt();
-        }
-        Color::new(
-            clamp(self.x, 0.0..=1.0),
-            clamp(self.y, 0.0..=1.0),
-            clamp(self.z, 0.0..=1.0),
-        )
-    }
 }
 
 impl Display for Vec3 {
@@ -292,12 +287,7 @@ impl Mul<&Self> for Vec3 {
 impl Mul<&Color> for &Vec3 {
     type Output = Vec3;
     fn mul(self, rhs: &Color) -> Self::Output {
-        let rhs = rhs.f();
-        Vec3::new(
-            self.x * rhs.r as f64,
-            self.y * rhs.g as f64,
-            self.z * rhs.b as f64,
-        )
+        Vec3::new(self.x * rhs.r, self.y * rhs.g, self.z * rhs.b)
     }
 }
 
@@ -464,7 +454,12 @@ impl Sum for Vec3 {
 
 impl From<Color> for Vec3 {
     fn from(c: Color) -> Self {
-        let c = c.f();
-        Self::new(c.r as f64, c.g as f64, c.b as f64)
+        Self::new(c.r, c.g, c.b)
+    }
+}
+
+impl From<Vec3> for Color {
+    fn from(v: Vec3) -> Self {
+        Self::new(v.x, v.y, v.z)
     }
 }
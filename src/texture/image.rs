@@ -9,14 +9,16 @@ use {
 
 pub struct Image {
     img: DynamicImage,
+    color_space: ColorSpace,
 }
 
 impl Debug for Image {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "Image {{ {}x{} }} ",
+            "Image {{ {}x{}, {:?} }} ",
             self.img.width(),
-            self.img.height()
+            self.img.height(),
+            self.color_space
         ))
     }
 }
@@ -27,7 +29,17 @@ impl Image {
     /// When load image failed
     pub fn new<P: AsRef<Path>>(p: P) -> Result<Self, String> {
         let img = image::open(p).map_err(|e| e.to_string())?;
-        Ok(Self { img })
+        Ok(Self {
+            img,
+            color_space: ColorSpace::SRGB,
+        })
+    }
+
+    /// Color space pixels are stored in, default is sRGB
+    #[must_use]
+    pub const fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
     }
 }
 
@@ -45,6 +57,7 @@ impl Texture for Image {
         }
 
         let color = self.img.get_pixel(px, py);
-        Color::new_int(color.0[0], color.0[1], color.0[2])
+        self.color_space
+            .decode(&Color::new_int(color.0[0], color.0[1], color.0[2]))
     }
 }
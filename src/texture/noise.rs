@@ -189,22 +189,23 @@ fn interpolate(
 
 impl Texture for Perlin {
     fn color(&self, _u: f64, _v: f64, point: &Point3) -> Color {
-        Color::new(1.0, 1.0, 1.0)
-            * match &self.texture_type {
-                TextureType::Normal => {
-                    let p = self.scale * point;
-                    let mut noise = self.noise(&p);
-
-                    if let RandomValueType::Vector(_) = &self.random_values[0] {
-                        noise = 0.5 * (noise + 1.0);
-                    }
-                    noise
-                }
-                TextureType::Turbulence(depth) => self.calculate_turbulence(point, *depth as usize),
-                TextureType::Marble(depth) => {
-                    let noise = self.calculate_turbulence(point, *depth as usize);
-                    (self.scale.mul_add(point.z, 10.0 * noise).sin() + 1.0) * 0.5
+        // Turbulence can go beyond 1, but reflectance can not
+        let value = match &self.texture_type {
+            TextureType::Normal => {
+                let p = self.scale * point;
+                let mut noise = self.noise(&p);
+
+                if let RandomValueType::Vector(_) = &self.random_values[0] {
+                    noise = 0.5 * (noise + 1.0);
                 }
+                noise
+            }
+            TextureType::Turbulence(depth) => self.calculate_turbulence(point, *depth as usize),
+            TextureType::Marble(depth) => {
+                let noise = self.calculate_turbulence(point, *depth as usize);
+                (self.scale.mul_add(point.z, 10.0 * noise).sin() + 1.0) * 0.5
             }
+        };
+        Color::new(1.0, 1.0, 1.0) * clamp(value, 0.0..=1.0)
     }
 }
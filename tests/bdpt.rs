@@ -0,0 +1,115 @@
+use remda::{
+    camera::CameraBuilder,
+    hittable::{
+        collection::{HittableList, World},
+        AARect, AARectMetrics, Carton,
+    },
+    integrator::{BidirectionalPathTracer, Integrator, MisStrategy, PathTracer},
+    material::{DiffuseLight, Lambertian},
+    prelude::*,
+};
+
+const SIZE: usize = 16;
+const TILES: usize = 4;
+const SAMPLES: usize = 2048;
+const DEPTH: usize = 8;
+
+fn cornell_box() -> World {
+    let red = Lambertian::new(Color::new(0.65, 0.05, 0.05));
+    let green = Lambertian::new(Color::new(0.12, 0.45, 0.15));
+    let white = Lambertian::new(Color::new(0.73, 0.73, 0.73));
+    let light = DiffuseLight::new(Color::new(1.0, 1.0, 1.0)).multiplier(15.0);
+
+    let mut objects = HittableList::default();
+    objects
+        .add(AARect::new_yz(
+            AARectMetrics::new(555.0, (0.0, 555.0), (0.0, 555.0)),
+            green,
+        ))
+        .add(AARect::new_yz(
+            AARectMetrics::new(0.0, (0.0, 555.0), (0.0, 555.0)),
+            red,
+        ))
+        .add(AARect::new_xz(
+            AARectMetrics::new(0.0, (0.0, 555.0), (0.0, 555.0)),
+            white.clone(),
+        ))
+        .add(AARect::new_xz(
+            AARectMetrics::new(555.0, (0.0, 555.0), (0.0, 555.0)),
+            white.clone(),
+        ))
+        .add(AARect::new_xy(
+            AARectMetrics::new(555.0, (0.0, 555.0), (0.0, 555.0)),
+            white.clone(),
+        ))
+        .add(AARect::new_xz(
+            AARectMetrics::new(554.0, (213.0, 343.0), (227.0, 332.0)),
+            light,
+        ))
+        .add(Carton::new(
+            Point3::new(265.0, 0.0, 295.0),
+            Point3::new(430.0, 330.0, 460.0),
+            white,
+        ));
+
+    let mut world = World::new(objects, 0.0..0.0);
+    world.set_bg(|_| Color::default());
+    world
+}
+
+// Mean radiance of each tile in a low resolution render, row by row
+fn render<I: Integrator>(integrator: &I, world: &World) -> Vec<Vec3> {
+    let camera = CameraBuilder::default()
+        .aspect_ratio(1.0)
+        .fov(40.0)
+        .look_from(Point3::new(278.0, 278.0, -800.0))
+        .look_at(Point3::new(278.0, 278.0, 0.0))
+        .build();
+
+    let tile = SIZE / TILES;
+    let mut tiles = vec![Vec3::default(); TILES * TILES];
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            for _ in 0..SAMPLES {
+                let u = (x as f64 + Random::normal()) / SIZE as f64;
+                let v = (y as f64 + Random::normal()) / SIZE as f64;
+                tiles[y / tile * TILES + x / tile] +=
+                    integrator.radiance(&camera.ray(u, v), world, DEPTH);
+            }
+        }
+    }
+    tiles
+        .into_iter()
+        .map(|sum| sum / (tile * tile * SAMPLES) as f64)
+        .collect()
+}
+
+#[test]
+fn bdpt_matches_path_tracer_in_cornell_box() {
+    let world = cornell_box();
+    let expected = render(&PathTracer::default().mis(MisStrategy::Power), &world);
+    let actual = render(&BidirectionalPathTracer, &world);
+
+    // Measured differences at this sample count stay under 3% for the whole picture
+    // and 7% for a channel of a tile, tolerances are about twice of that
+    let close = |a: f64, b: f64, tolerance: f64| (a - b).abs() <= tolerance * b.max(0.01);
+    let mean = |tiles: &[Vec3]| {
+        let sum = tiles.iter().fold(Vec3::default(), |sum, c| sum + c);
+        (sum.x + sum.y + sum.z) / (3 * tiles.len()) as f64
+    };
+    assert!(
+        close(mean(&actual), mean(&expected), 0.06),
+        "mean of bdpt {}, path tracer {}",
+        mean(&actual),
+        mean(&expected)
+    );
+    for (index, (a, e)) in actual.iter().zip(&expected).enumerate() {
+        assert!(
+            close(a.x, e.x, 0.15) && close(a.y, e.y, 0.15) && close(a.z, e.z, 0.15),
+            "tile {} of bdpt {:?}, path tracer {:?}",
+            index,
+            a,
+            e
+        );
+    }
+}